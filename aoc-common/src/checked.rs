@@ -0,0 +1,118 @@
+//! Arithmetic that reports overflow when a day is
+//! built with its `checked-math` feature.
+//!
+//! Without the feature
+//! [`checked!`](crate::checked) compiles to the
+//! plain operator, so release builds keep
+//! wrapping behaviour and speed while `cargo test
+//! --features checked-math` turns
+//! silent wrap-around into an [`Overflow`] error
+//! naming the expression and what was being
+//! computed.
+use miette::Diagnostic;
+use thiserror::Error;
+
+#[derive(Error, Diagnostic, Debug, PartialEq)]
+#[error("`{operation}` overflowed while {context}")]
+#[diagnostic(
+    code(aoc::overflow),
+    help("the input has values too large for the integer types this solution uses")
+)]
+pub struct Overflow {
+    /// The expression, as written in the source
+    pub operation: &'static str,
+    pub context: String,
+}
+
+/// `checked!(add(a, b), "context {}", a)` is
+/// `a + b`, or with the calling crate's
+/// `checked-math` feature `a.checked_add(b)` with
+/// an [`Overflow`] error built from the context.
+/// `sub`, `mul` and `pow` work the same way.
+///
+/// The feature is checked in the crate using the
+/// macro, so each day opts in on its own.
+#[macro_export]
+macro_rules! checked {
+    (add($a:expr, $b:expr), $($context:tt)+) => {
+        $crate::checked!(@op checked_add, ($a) + ($b), concat!(stringify!($a), " + ", stringify!($b)), $a, $b, $($context)+)
+    };
+    (sub($a:expr, $b:expr), $($context:tt)+) => {
+        $crate::checked!(@op checked_sub, ($a) - ($b), concat!(stringify!($a), " - ", stringify!($b)), $a, $b, $($context)+)
+    };
+    (mul($a:expr, $b:expr), $($context:tt)+) => {
+        $crate::checked!(@op checked_mul, ($a) * ($b), concat!(stringify!($a), " * ", stringify!($b)), $a, $b, $($context)+)
+    };
+    (pow($a:expr, $b:expr), $($context:tt)+) => {
+        $crate::checked!(@op checked_pow, ($a).pow($b), concat!(stringify!($a), ".pow(", stringify!($b), ")"), $a, $b, $($context)+)
+    };
+    (@op $method:ident, $unchecked:expr, $operation:expr, $a:expr, $b:expr, $($context:tt)+) => {{
+        #[cfg(feature = "checked-math")]
+        let result = ($a).$method($b).ok_or_else(|| {
+            $crate::checked::Overflow {
+                operation: $operation,
+                context: format!($($context)+),
+            }
+        });
+        #[cfg(not(feature = "checked-math"))]
+        let result: Result<_, $crate::checked::Overflow> = {
+            // keep variables only used for the context
+            // from being reported as unused
+            let _ = || format!($($context)+);
+            Ok($unchecked)
+        };
+        result
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_range() {
+        let a: u32 = 40;
+        let sum: Result<u32, Overflow> =
+            checked!(add(a, 2), "adding {a} and 2");
+        assert_eq!(Ok(42), sum);
+        assert_eq!(Ok(38), checked!(sub(a, 2), "sub"));
+        assert_eq!(Ok(80), checked!(mul(a, 2), "mul"));
+        assert_eq!(Ok(1600), checked!(pow(a, 2), "pow"));
+    }
+
+    #[cfg(feature = "checked-math")]
+    #[test]
+    fn test_overflow() {
+        let a = u64::MAX - 1;
+        assert_eq!(
+            Err(Overflow {
+                operation: "a + 2",
+                context: format!("adding {a} and 2"),
+            }),
+            checked!(add(a, 2), "adding {a} and 2")
+        );
+        assert!(checked!(sub(0_u8, 1), "sub").is_err());
+        assert!(checked!(mul(i64::MAX, 2), "mul").is_err());
+        assert_eq!(
+            "2_i64.pow(63)",
+            checked!(pow(2_i64, 63), "pow")
+                .unwrap_err()
+                .operation
+        );
+    }
+
+    #[cfg(feature = "checked-math")]
+    #[test]
+    fn test_message() {
+        let error = checked!(
+            mul(i32::MAX, 2),
+            "doubling {}",
+            i32::MAX
+        )
+        .unwrap_err();
+        assert_eq!(
+            "`i32::MAX * 2` overflowed while doubling 2147483647",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! Puzzle inputs are committed encrypted with
+//! [age] so that they aren't published in plain
+//! text. Each user's inputs live in
+//! `inputs/<user>/day-01.txt`, one per day for
+//! both parts, and [`load_day`] reads the
+//! [`DEFAULT_USER`]'s.
+//!
+//! [`load`] reads `day-01.txt` directly when a
+//! plaintext copy exists locally, otherwise it
+//! decrypts `day-01.txt.age` with the key found
+//! by [`find_key`].
+//!
+//! [age]: https://age-encryption.org
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use age::{secrecy::ExposeSecret, x25519};
+use miette::Diagnostic;
+use thiserror::Error;
+use tracing::debug;
+
+/// Environment variable holding an age secret key
+/// (`AGE-SECRET-KEY-1...`).
+pub const KEY_ENV: &str = "AOC_INPUT_KEY";
+/// Environment variable holding the path to an
+/// age key file, as written by `age-keygen`.
+pub const KEY_FILE_ENV: &str = "AOC_INPUT_KEY_FILE";
+/// Key file looked up in the workspace root when
+/// neither environment variable is set.
+pub const KEY_FILE: &str = ".aoc-key";
+/// Extension appended to encrypted input files.
+pub const ENCRYPTED_EXTENSION: &str = "age";
+/// Directory in the workspace root holding a
+/// directory of inputs per user.
+pub const INPUTS_DIR: &str = "inputs";
+/// The user whose inputs the day binaries,
+/// benches and tests use.
+pub const DEFAULT_USER: &str = "default";
+
+#[derive(Error, Diagnostic, Debug)]
+pub enum InputError {
+    #[error("no input found at `{}` or its `.age` counterpart", .0.display())]
+    #[diagnostic(
+        code(aoc::input::missing),
+        help("fetch it with `scripts/get-aoc-input.rs`")
+    )]
+    Missing(PathBuf),
+
+    #[error("`{}` is encrypted but no input key is configured", .0.display())]
+    #[diagnostic(
+        code(aoc::input::no_key),
+        help(
+            "set {KEY_ENV} to an age secret key, set {KEY_FILE_ENV} to a key file, or create `{KEY_FILE}` in the workspace root"
+        )
+    )]
+    NoKey(PathBuf),
+
+    #[error("invalid input key from {origin}: {reason}")]
+    #[diagnostic(code(aoc::input::invalid_key))]
+    InvalidKey {
+        origin: String,
+        reason: &'static str,
+    },
+
+    #[error("failed to decrypt `{}`", .path.display())]
+    #[diagnostic(
+        code(aoc::input::decrypt),
+        help("is the input key the one the inputs were encrypted with?")
+    )]
+    Decrypt {
+        path: PathBuf,
+        #[source]
+        source: age::DecryptError,
+    },
+
+    #[error(transparent)]
+    #[diagnostic(code(aoc::input::encrypt))]
+    Encrypt(#[from] age::EncryptError),
+
+    #[error("`{}` is not valid utf-8", .0.display())]
+    #[diagnostic(code(aoc::input::utf8))]
+    Utf8(PathBuf),
+
+    #[error(transparent)]
+    #[diagnostic(code(aoc::io_error))]
+    IoError(#[from] std::io::Error),
+}
+
+/// Load the input `name` from `dir`.
+#[tracing::instrument]
+pub fn load(
+    dir: impl AsRef<Path> + std::fmt::Debug,
+    name: &str,
+) -> Result<String, InputError> {
+    let path = dir.as_ref().join(name);
+    if path.exists() {
+        debug!(?path, "reading plaintext input");
+        return Ok(fs::read_to_string(path)?);
+    }
+    let encrypted = encrypted_path(&path);
+    if !encrypted.exists() {
+        return Err(InputError::Missing(path));
+    }
+    let identity = find_key()?.ok_or_else(|| {
+        InputError::NoKey(encrypted.clone())
+    })?;
+    debug!(?encrypted, "decrypting input");
+    decrypt_file(&encrypted, &identity)
+}
+
+/// Load the [`DEFAULT_USER`]'s input for `day`,
+/// usually `env!("CARGO_PKG_NAME")`.
+pub fn load_day(day: &str) -> Result<String, InputError> {
+    load(
+        crate::workspace_root()
+            .join(INPUTS_DIR)
+            .join(DEFAULT_USER),
+        &format!("{day}.txt"),
+    )
+}
+
+/// Path of the encrypted counterpart of `path`.
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut encrypted = path.as_os_str().to_owned();
+    encrypted.push(".");
+    encrypted.push(ENCRYPTED_EXTENSION);
+    PathBuf::from(encrypted)
+}
+
+/// Look for the input key in [`KEY_ENV`], then
+/// [`KEY_FILE_ENV`], then [`KEY_FILE`] in the
+/// workspace root.
+pub fn find_key(
+) -> Result<Option<x25519::Identity>, InputError> {
+    if let Ok(key) = std::env::var(KEY_ENV) {
+        return parse_key(&key, KEY_ENV).map(Some);
+    }
+    let path = match std::env::var(KEY_FILE_ENV) {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => crate::workspace_root().join(KEY_FILE),
+    };
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(&path)?;
+    parse_key(&contents, &path.display().to_string())
+        .map(Some)
+}
+
+/// Parse a secret key, skipping the `#` comment
+/// lines `age-keygen` writes into key files.
+pub fn parse_key(
+    contents: &str,
+    origin: &str,
+) -> Result<x25519::Identity, InputError> {
+    let key = contents
+        .lines()
+        .map(str::trim)
+        .find(|line| {
+            !line.is_empty() && !line.starts_with('#')
+        })
+        .ok_or_else(|| InputError::InvalidKey {
+            origin: origin.to_string(),
+            reason: "no key found",
+        })?;
+    x25519::Identity::from_str(key).map_err(|reason| {
+        InputError::InvalidKey {
+            origin: origin.to_string(),
+            reason,
+        }
+    })
+}
+
+/// Render a new key in the same format as
+/// `age-keygen`.
+pub fn generate_key() -> (x25519::Identity, String) {
+    let identity = x25519::Identity::generate();
+    let contents = format!(
+        "# public key: {}\n{}\n",
+        identity.to_public(),
+        identity.to_string().expose_secret()
+    );
+    (identity, contents)
+}
+
+pub fn decrypt_file(
+    path: &Path,
+    identity: &x25519::Identity,
+) -> Result<String, InputError> {
+    let ciphertext = fs::read(path)?;
+    let plaintext = age::decrypt(identity, &ciphertext)
+        .map_err(|source| InputError::Decrypt {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    String::from_utf8(plaintext)
+        .map_err(|_| InputError::Utf8(path.to_path_buf()))
+}
+
+/// Encrypt `path` next to itself, returning the
+/// path of the armored `.age` file.
+pub fn encrypt_file(
+    path: &Path,
+    recipient: &x25519::Recipient,
+) -> Result<PathBuf, InputError> {
+    let plaintext = fs::read(path)?;
+    let armored =
+        age::encrypt_and_armor(recipient, &plaintext)?;
+    let encrypted = encrypted_path(path);
+    fs::write(&encrypted, armored)?;
+    Ok(encrypted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "aoc-common-{name}-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_encrypt_round_trip() -> miette::Result<()> {
+        let dir = temp_dir("round-trip");
+        let path = dir.join("day-01.txt");
+        fs::write(&path, "1abc2\npqr3stu8vwx\n").unwrap();
+        let (identity, _) = generate_key();
+        let encrypted =
+            encrypt_file(&path, &identity.to_public())?;
+        assert_eq!(encrypted, dir.join("day-01.txt.age"));
+        assert!(fs::read_to_string(&encrypted)
+            .unwrap()
+            .starts_with(
+                "-----BEGIN AGE ENCRYPTED FILE-----"
+            ));
+        assert_eq!(
+            "1abc2\npqr3stu8vwx\n",
+            decrypt_file(&encrypted, &identity)?
+        );
+        fs::remove_dir_all(dir).unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn test_decrypt_with_wrong_key() -> miette::Result<()> {
+        let dir = temp_dir("wrong-key");
+        let path = dir.join("day-01.txt");
+        fs::write(&path, "secret").unwrap();
+        let (identity, _) = generate_key();
+        let (other, _) = generate_key();
+        let encrypted =
+            encrypt_file(&path, &identity.to_public())?;
+        assert!(matches!(
+            decrypt_file(&encrypted, &other),
+            Err(InputError::Decrypt { .. })
+        ));
+        fs::remove_dir_all(dir).unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_key_file() {
+        let (identity, contents) = generate_key();
+        let parsed = parse_key(&contents, "test").unwrap();
+        assert_eq!(
+            identity.to_public().to_string(),
+            parsed.to_public().to_string()
+        );
+        assert!(matches!(
+            parse_key("# only a comment\n", "test"),
+            Err(InputError::InvalidKey { .. })
+        ));
+        assert!(matches!(
+            parse_key("AGE-SECRET-KEY-1NOPE", "test"),
+            Err(InputError::InvalidKey { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_prefers_plaintext() -> miette::Result<()> {
+        let dir = temp_dir("plaintext");
+        fs::write(dir.join("day-01.txt"), "plain").unwrap();
+        fs::write(dir.join("day-01.txt.age"), "garbage")
+            .unwrap();
+        assert_eq!("plain", load(&dir, "day-01.txt")?);
+        assert!(matches!(
+            load(&dir, "day-02.txt"),
+            Err(InputError::Missing(_))
+        ));
+        fs::remove_dir_all(dir).unwrap();
+        Ok(())
+    }
+}
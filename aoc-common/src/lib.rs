@@ -0,0 +1,20 @@
+pub mod checked;
+pub mod input;
+pub mod lines;
+pub mod params;
+pub mod parse;
+pub mod variants;
+#[cfg(feature = "visualize")]
+pub mod visualize;
+
+use std::path::{Path, PathBuf};
+
+/// Root of the cargo workspace, used to find
+/// files shared between days such as the input
+/// key.
+pub fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("aoc-common lives inside the workspace")
+        .to_path_buf()
+}
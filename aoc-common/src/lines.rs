@@ -0,0 +1,197 @@
+//! Reading input one line at a time, whichever
+//! way it was saved.
+//!
+//! Inputs saved on Windows end lines with `\r\n`,
+//! and editors add or drop the final newline,
+//! leave trailing blank lines or trailing spaces.
+//! [`input_lines`] and [`try_for_each_line`] hide
+//! all of these, and [`assert_all_formats`]
+//! checks a solution against each of them.
+use std::{
+    fmt::{Debug, Display},
+    io::BufRead,
+};
+
+/// The lines of `input` with trailing whitespace
+/// (including a `\r` line ending) removed and
+/// trailing blank lines dropped. Blank lines
+/// between lines are kept.
+pub fn input_lines(
+    input: &str,
+) -> impl Iterator<Item = &str> {
+    input.trim_end().lines().map(str::trim_end)
+}
+
+/// Call `f` with each line of `reader` as
+/// [`input_lines`] would return them, reusing a
+/// single buffer so memory is bounded by the
+/// longest line rather than the whole input.
+pub fn try_for_each_line<E>(
+    mut reader: impl BufRead,
+    mut f: impl FnMut(&str) -> Result<(), E>,
+) -> Result<(), E>
+where
+    E: From<std::io::Error>,
+{
+    let mut buffer = String::new();
+    // blank lines are only passed on once a later
+    // line shows they aren't trailing
+    let mut blank_lines = 0;
+    loop {
+        buffer.clear();
+        if reader.read_line(&mut buffer)? == 0 {
+            return Ok(());
+        }
+        let line = buffer.trim_end();
+        if line.is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        for _ in 0..blank_lines {
+            f("")?;
+        }
+        blank_lines = 0;
+        f(line)?;
+    }
+}
+
+/// `input` in each format an input may be saved
+/// in, labelled for test failures
+pub fn input_formats(
+    input: &str,
+) -> Vec<(&'static str, String)> {
+    let lines = input_lines(input).collect::<Vec<_>>();
+    let lf = lines.join("\n");
+    let crlf = lines.join("\r\n");
+    let trailing_whitespace = lines
+        .iter()
+        .map(|line| format!("{line} \t "))
+        .collect::<Vec<_>>()
+        .join("\n");
+    vec![
+        ("no final newline", lf.clone()),
+        ("final newline", format!("{lf}\n")),
+        (
+            "trailing blank lines",
+            format!("{lf}\n\n \n"),
+        ),
+        ("crlf", format!("{crlf}\r\n")),
+        ("crlf, no final newline", crlf),
+        (
+            "trailing whitespace",
+            format!("{trailing_whitespace}\n"),
+        ),
+    ]
+}
+
+/// Check `process` gives `expected` for `input`
+/// in every one of its [`input_formats`]
+pub fn assert_all_formats<T, E>(
+    process: impl Fn(&str) -> Result<T, E>,
+    input: &str,
+    expected: T,
+) where
+    T: PartialEq + Debug,
+    E: Display,
+{
+    let failures = input_formats(input)
+        .into_iter()
+        .filter_map(|(format, input)| {
+            match process(&input) {
+                Ok(answer) if answer == expected => None,
+                Ok(answer) => Some(format!(
+                    "{format}: expected {expected:?}, got {answer:?}"
+                )),
+                Err(error) => {
+                    Some(format!("{format}: {error}"))
+                }
+            }
+        })
+        .collect::<Vec<String>>();
+    assert!(failures.is_empty(), "{failures:#?}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(input: &str) -> Vec<String> {
+        let mut lines = vec![];
+        try_for_each_line(input.as_bytes(), |line| {
+            lines.push(line.to_string());
+            Ok::<_, std::io::Error>(())
+        })
+        .unwrap();
+        lines
+    }
+
+    #[test]
+    fn test_matches_input_lines() {
+        for input in [
+            "",
+            "a",
+            "a\nb",
+            "a\nb\n",
+            "a\r\nb\r\n",
+            "a\n\nb",
+            "a\rb\n",
+            "a \nb\t\n\n\r\n",
+        ] {
+            assert_eq!(
+                input_lines(input).collect::<Vec<_>>(),
+                collect(input),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_input_lines() {
+        assert_eq!(
+            vec!["a", "", "b"],
+            input_lines("a  \r\n\r\nb\t\r\n\r\n")
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_input_formats() {
+        for (format, input) in input_formats("a b\n\nc\n") {
+            assert_eq!(
+                vec!["a b", "", "c"],
+                input_lines(&input).collect::<Vec<_>>(),
+                "{format}"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "trailing blank lines")]
+    fn test_assert_all_formats_reports_format() {
+        assert_all_formats(
+            |input| Ok::<_, String>(input.lines().count()),
+            "a\nb",
+            2,
+        );
+    }
+
+    #[test]
+    fn test_stops_at_first_error() {
+        let mut seen = 0;
+        let result = try_for_each_line(
+            "1\nx\n3".as_bytes(),
+            |line| {
+                seen += 1;
+                line.parse::<u32>().map(|_| ()).map_err(
+                    |_| {
+                        std::io::Error::other(
+                            line.to_string(),
+                        )
+                    },
+                )
+            },
+        );
+        assert_eq!("x", result.unwrap_err().to_string());
+        assert_eq!(2, seen);
+    }
+}
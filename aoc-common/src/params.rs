@@ -0,0 +1,293 @@
+//! Puzzle constants, such as how many cubes are
+//! in the bag or how far the universe expands,
+//! kept in a typed struct with the puzzle's
+//! values as defaults.
+//!
+//! [`params!`](crate::params) declares the struct
+//! and implements [`Params`] for it, so the day
+//! binaries can take overrides with `--param
+//! factor=10` and tests can use
+//! `Params::with(["factor=10"])` or plain struct
+//! update syntax.
+use std::{fmt::Display, str::FromStr};
+
+use miette::Diagnostic;
+use thiserror::Error;
+
+#[derive(Error, Diagnostic, Debug, PartialEq)]
+pub enum ParamError {
+    #[error("expected `name=value`, got `{0}`")]
+    #[diagnostic(code(aoc::param_error))]
+    Syntax(String),
+
+    #[error("unknown parameter `{name}`")]
+    #[diagnostic(
+        code(aoc::param_error),
+        help("this part takes {known}")
+    )]
+    Unknown { name: String, known: String },
+
+    #[error("`{value}` isn't a valid `{name}`: {reason}")]
+    #[diagnostic(code(aoc::param_error))]
+    Invalid {
+        name: String,
+        value: String,
+        reason: String,
+    },
+
+    #[error("unexpected argument `{0}`")]
+    #[diagnostic(
+        code(aoc::param_error),
+        help("pass parameters as `--param name=value`")
+    )]
+    Usage(String),
+}
+
+/// A day's puzzle constants, usually declared
+/// with [`params!`](crate::params)
+pub trait Params: Default {
+    /// Every parameter's name and current value
+    fn values(&self) -> Vec<(&'static str, String)>;
+
+    /// Set the parameter `name` from its text
+    /// form
+    fn set(
+        &mut self,
+        name: &str,
+        value: &str,
+    ) -> Result<(), ParamError>;
+
+    /// The defaults with `name=value` overrides
+    /// applied in order
+    fn with<S: AsRef<str>>(
+        overrides: impl IntoIterator<Item = S>,
+    ) -> Result<Self, ParamError> {
+        let mut params = Self::default();
+        for item in overrides {
+            let item = item.as_ref();
+            let (name, value) =
+                item.split_once('=').ok_or_else(|| {
+                    ParamError::Syntax(item.to_string())
+                })?;
+            params.set(name.trim(), value.trim())?;
+        }
+        Ok(params)
+    }
+
+    /// The defaults with the `--param name=value`
+    /// overrides from the command line applied
+    fn from_args() -> Result<Self, ParamError> {
+        Self::with(overrides(std::env::args().skip(1))?)
+    }
+}
+
+/// The `name=value` overrides from `--param
+/// name=value`, `--param=name=value` or `-p
+/// name=value` arguments
+pub fn overrides(
+    args: impl IntoIterator<Item = String>,
+) -> Result<Vec<String>, ParamError> {
+    let mut args = args.into_iter();
+    let mut overrides = vec![];
+    while let Some(arg) = args.next() {
+        if let Some(item) = arg.strip_prefix("--param=") {
+            overrides.push(item.to_string());
+        } else if arg == "--param" || arg == "-p" {
+            overrides.push(args.next().ok_or_else(
+                || {
+                    ParamError::Syntax(format!(
+                        "nothing after `{arg}`"
+                    ))
+                },
+            )?);
+        } else {
+            return Err(ParamError::Usage(arg));
+        }
+    }
+    Ok(overrides)
+}
+
+/// Parse a parameter's value, naming the
+/// parameter in the error
+pub fn parse_value<T>(
+    name: &str,
+    value: &str,
+) -> Result<T, ParamError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value.parse().map_err(|error: T::Err| {
+        ParamError::Invalid {
+            name: name.to_string(),
+            value: value.to_string(),
+            reason: error.to_string(),
+        }
+    })
+}
+
+/// Declare a struct of puzzle constants with
+/// their defaults and implement
+/// [`Params`](crate::params::Params) for it.
+/// Each field's type needs `FromStr` and
+/// `Display`.
+///
+/// ```
+/// aoc_common::params! {
+///     pub struct Params {
+///         /// how many times larger empty space is
+///         factor: u64 = 1_000_000,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! params {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$field_meta:meta])*
+                $field:ident: $ty:ty = $default:expr
+            ),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq)]
+        $vis struct $name {
+            $(
+                $(#[$field_meta])*
+                pub $field: $ty,
+            )+
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self { $($field: $default,)+ }
+            }
+        }
+
+        impl $crate::params::Params for $name {
+            fn values(&self) -> Vec<(&'static str, String)> {
+                vec![$(
+                    (stringify!($field), self.$field.to_string()),
+                )+]
+            }
+
+            fn set(
+                &mut self,
+                name: &str,
+                value: &str,
+            ) -> Result<(), $crate::params::ParamError> {
+                match name {
+                    $(stringify!($field) => {
+                        self.$field =
+                            $crate::params::parse_value(name, value)?;
+                    })+
+                    _ => {
+                        let known = <Self as Default>::default()
+                            .values()
+                            .into_iter()
+                            .map(|(name, value)| {
+                                format!("`{name}` (default {value})")
+                            })
+                            .collect::<Vec<String>>()
+                            .join(", ");
+                        return Err($crate::params::ParamError::Unknown {
+                            name: name.to_string(),
+                            known,
+                        });
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::params! {
+        struct Bag {
+            /// most red cubes
+            red: usize = 12,
+            blue: usize = 14,
+        }
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_with() -> Result<(), ParamError> {
+        assert_eq!(
+            Bag { red: 12, blue: 14 },
+            Bag::default()
+        );
+        assert_eq!(
+            Bag { red: 20, blue: 1 },
+            Bag::with(["red=20", "blue = 1"])?
+        );
+        assert_eq!(
+            vec![
+                ("red", "12".to_string()),
+                ("blue", "14".to_string())
+            ],
+            Bag::default().values()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Err(ParamError::Syntax("red".to_string())),
+            Bag::with(["red"])
+        );
+        assert_eq!(
+            Err(ParamError::Unknown {
+                name: "green".to_string(),
+                known: "`red` (default 12), `blue` (default 14)"
+                    .to_string()
+            }),
+            Bag::with(["green=1"])
+        );
+        let Err(ParamError::Invalid {
+            name, value, ..
+        }) = Bag::with(["red=-1"])
+        else {
+            panic!("expected an invalid value");
+        };
+        assert_eq!(
+            ("red", "-1"),
+            (name.as_str(), value.as_str())
+        );
+    }
+
+    #[test]
+    fn test_overrides() {
+        assert_eq!(
+            Ok(args(&["a=1", "b=2", "c=3"])),
+            overrides(args(&[
+                "--param",
+                "a=1",
+                "--param=b=2",
+                "-p",
+                "c=3"
+            ]))
+        );
+        assert_eq!(Ok(vec![]), overrides(vec![]));
+        assert_eq!(
+            Err(ParamError::Usage(
+                "--factor".to_string()
+            )),
+            overrides(args(&["--factor", "10"]))
+        );
+        assert!(matches!(
+            overrides(args(&["--param"])),
+            Err(ParamError::Syntax(_))
+        ));
+    }
+}
@@ -0,0 +1,354 @@
+//! nom combinators for the shapes puzzle inputs
+//! come in, and [`parse_all`] to run one over a
+//! whole input, reporting failures as a
+//! [`ParseError`] that miette renders with the
+//! offending text underlined.
+//!
+//! The combinators use nom's [`VerboseError`] so
+//! the error can name what was expected and what
+//! was being parsed at the time.
+use std::str::FromStr;
+
+use miette::{Diagnostic, SourceSpan};
+use nom::{
+    bytes::complete::{tag, take_till1},
+    character::complete::{
+        char, digit1, line_ending, multispace0,
+        not_line_ending, one_of, space0, space1,
+    },
+    combinator::{
+        all_consuming, map, map_res, opt, recognize, verify,
+    },
+    error::{
+        context, ErrorKind, VerboseError, VerboseErrorKind,
+    },
+    multi::{many1, separated_list1},
+    sequence::{
+        pair, preceded, separated_pair, terminated, tuple,
+    },
+    IResult, Parser,
+};
+use thiserror::Error;
+
+pub type PResult<'a, O> =
+    IResult<&'a str, O, VerboseError<&'a str>>;
+
+#[derive(Error, Diagnostic, Debug)]
+#[error("couldn't parse the input, expected {expected}")]
+#[diagnostic(code(aoc::parse_error))]
+pub struct ParseError {
+    pub expected: String,
+    #[source_code]
+    input: String,
+    #[label("here")]
+    span: SourceSpan,
+    #[help]
+    help: Option<String>,
+}
+
+impl ParseError {
+    fn new(
+        input: &str,
+        error: nom::Err<VerboseError<&str>>,
+    ) -> Self {
+        let errors = match error {
+            nom::Err::Error(error)
+            | nom::Err::Failure(error) => error.errors,
+            nom::Err::Incomplete(_) => vec![],
+        };
+        let offset = errors
+            .first()
+            .map_or(input.len(), |(rest, _)| {
+                input.len() - rest.len()
+            });
+        let mut contexts = errors.iter().filter_map(
+            |(_, kind)| match kind {
+                VerboseErrorKind::Context(context) => {
+                    Some(*context)
+                }
+                _ => None,
+            },
+        );
+        // the innermost context says what was
+        // expected, the rest where it was expected
+        let expected = match errors.first() {
+            Some((_, VerboseErrorKind::Char(c))) => {
+                format!("`{c}`")
+            }
+            Some((
+                _,
+                VerboseErrorKind::Nom(ErrorKind::Eof),
+            )) => "the end of the input".to_string(),
+            Some((_, VerboseErrorKind::Nom(kind))) => {
+                contexts.next().map_or_else(
+                    || kind.description().to_lowercase(),
+                    str::to_string,
+                )
+            }
+            Some((_, VerboseErrorKind::Context(_))) => {
+                contexts
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            }
+            None => "more input".to_string(),
+        };
+        let outer = contexts.collect::<Vec<&str>>();
+        Self {
+            expected,
+            input: input.to_string(),
+            span: SourceSpan::from((offset, 0)),
+            help: (!outer.is_empty()).then(|| {
+                format!(
+                    "while parsing {}",
+                    outer.join(" in ")
+                )
+            }),
+        }
+    }
+
+    /// Byte offset of the failure in the input
+    pub fn offset(&self) -> usize {
+        self.span.offset()
+    }
+}
+
+/// Run `parser` over the whole of `input`,
+/// ignoring trailing whitespace
+pub fn parse_all<'a, O>(
+    input: &'a str,
+    parser: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> Result<O, ParseError> {
+    all_consuming(terminated(parser, multispace0))(input)
+        .map(|(_, output)| output)
+        .map_err(|error| ParseError::new(input, error))
+}
+
+/// An integer of any width, with an optional sign
+/// for signed types
+pub fn integer<T: FromStr>(input: &str) -> PResult<'_, T> {
+    context(
+        "a number",
+        map_res(
+            recognize(pair(opt(one_of("+-")), digit1)),
+            str::parse,
+        ),
+    )(input)
+}
+
+/// Integers separated by spaces, such as
+/// `79 14 55 13`
+pub fn numbers<T: FromStr>(
+    input: &str,
+) -> PResult<'_, Vec<T>> {
+    separated_list1(space1, integer)(input)
+}
+
+/// `label:` then spaces then `parser`, such as
+/// `labelled("Time", numbers)` for
+/// `Time:      7  15   30`
+pub fn labelled<'a, O>(
+    label: &'static str,
+    parser: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, O> {
+    context(
+        label,
+        preceded(
+            tuple((tag(label), char(':'), space0)),
+            parser,
+        ),
+    )
+}
+
+/// A `key: value` line, giving the key and the
+/// rest of the line without trailing whitespace
+pub fn key_value(input: &str) -> PResult<'_, (&str, &str)> {
+    context(
+        "`key: value`",
+        map(
+            separated_pair(
+                take_till1(|c| {
+                    matches!(c, ':' | '\n' | '\r')
+                }),
+                pair(char(':'), space0),
+                not_line_ending,
+            ),
+            |(key, value): (&str, &str)| {
+                (key, value.trim_end())
+            },
+        ),
+    )(input)
+}
+
+/// The end of a line, allowing trailing
+/// whitespace before it
+pub fn end_of_line(input: &str) -> PResult<'_, &str> {
+    recognize(pair(space0, line_ending))(input)
+}
+
+/// Lines each parsed by `parser`. Stops before a
+/// blank line, so [`blocks`] of lines can be
+/// parsed.
+pub fn lines<'a, O>(
+    parser: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<O>> {
+    separated_list1(end_of_line, parser)
+}
+
+/// Blocks separated by one or more blank lines,
+/// each parsed by `parser`
+pub fn blocks<'a, O>(
+    parser: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<O>> {
+    separated_list1(
+        recognize(pair(end_of_line, many1(end_of_line))),
+        parser,
+    )
+}
+
+/// A rectangular grid with each character turned
+/// into a cell by `cell`, rows first. A character
+/// `cell` rejects is an error rather than the end
+/// of the grid.
+pub fn grid<'a, T>(
+    cell: impl Fn(char) -> Option<T>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<Vec<T>>> {
+    let row = move |input: &'a str| {
+        let (rest, line) =
+            take_till1(|c| matches!(c, '\n' | '\r'))(
+                input,
+            )?;
+        line.trim_end()
+            .char_indices()
+            .map(|(i, c)| cell(c).ok_or(i))
+            .collect::<Result<Vec<T>, usize>>()
+            .map(|cells| (rest, cells))
+            .map_err(|i| {
+                nom::Err::Failure(VerboseError {
+                    errors: vec![(
+                        &input[i..],
+                        VerboseErrorKind::Context(
+                            "a grid cell",
+                        ),
+                    )],
+                })
+            })
+    };
+    context(
+        "a rectangular grid",
+        verify(lines(row), |rows: &Vec<Vec<T>>| {
+            rows.iter()
+                .all(|row| row.len() == rows[0].len())
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer() {
+        assert_eq!(Ok(("", -12_i64)), integer("-12"));
+        assert_eq!(Ok((" 3", 7_u8)), integer("+7 3"));
+        assert_eq!(
+            Ok(("", u128::MAX)),
+            integer(&u128::MAX.to_string())
+        );
+        // out of range for the type
+        assert!(integer::<u8>("256").is_err());
+        assert!(integer::<u32>("-1").is_err());
+    }
+
+    #[test]
+    fn test_labelled() -> Result<(), ParseError> {
+        let (times, distances) = parse_all(
+            "Time:      7  15   30\nDistance:  9  40  200\n",
+            separated_pair(
+                labelled("Time", numbers::<u32>),
+                end_of_line,
+                labelled("Distance", numbers::<u32>),
+            ),
+        )?;
+        assert_eq!(vec![7, 15, 30], times);
+        assert_eq!(vec![9, 40, 200], distances);
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_value() {
+        assert_eq!(
+            Ok(("\nb", ("Card 1", "41 48 | 83"))),
+            key_value("Card 1: 41 48 | 83 \nb")
+        );
+    }
+
+    #[test]
+    fn test_blocks() -> Result<(), ParseError> {
+        let input = "1 2\n3 \n\n4\n \r\n\n5 6\n";
+        assert_eq!(
+            vec![
+                vec![vec![1, 2], vec![3]],
+                vec![vec![4]],
+                vec![vec![5, 6]]
+            ],
+            parse_all(input, blocks(lines(numbers::<u8>)))?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_grid() -> Result<(), ParseError> {
+        let cell = |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        };
+        assert_eq!(
+            vec![vec![true, false], vec![false, true]],
+            parse_all("#.\r\n.#\r\n", grid(cell))?
+        );
+        let error =
+            parse_all("#.\n.#.", grid(cell)).unwrap_err();
+        assert_eq!("a rectangular grid", error.expected);
+        let error =
+            parse_all("#.\n.x", grid(cell)).unwrap_err();
+        assert_eq!("a grid cell", error.expected);
+        assert_eq!(4, error.offset());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = "Time: 7 15\nDistance: 9 x\n";
+        let error = parse_all(
+            input,
+            separated_pair(
+                labelled("Time", numbers::<u32>),
+                end_of_line,
+                labelled("Distance", numbers::<u32>),
+            ),
+        )
+        .unwrap_err();
+        assert_eq!(
+            "couldn't parse the input, expected the end of the input",
+            error.to_string()
+        );
+        assert_eq!(
+            input.find('x').unwrap(),
+            error.offset()
+        );
+
+        let error = parse_all(
+            "Distance: x",
+            labelled("Distance", numbers::<u32>),
+        )
+        .unwrap_err();
+        assert_eq!("a number", error.expected);
+        assert_eq!(10, error.offset());
+        assert_eq!(
+            Some("while parsing Distance".to_string()),
+            error.help
+        );
+    }
+}
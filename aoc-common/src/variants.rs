@@ -0,0 +1,145 @@
+//! Named implementations of the same part, such
+//! as a naive version kept next to an optimised
+//! one.
+//!
+//! A part lists its implementations in a
+//! `VARIANTS` slice, the first entry being the
+//! one `process` uses. Tests run every variant
+//! through [`assert_variants`], in every one of
+//! the [`input_formats`], and the divan
+//! benches take the slice as `args`, so a new
+//! variant is tested and benchmarked by adding it
+//! to the list.
+use std::fmt::{self, Debug, Display};
+
+use crate::lines::input_formats;
+
+pub struct Variant<T, E> {
+    pub name: &'static str,
+    pub process: fn(&str) -> Result<T, E>,
+}
+
+/// Benches are labelled with the variant's name
+impl<T, E> Display for Variant<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// Check that every variant returns `expected`
+/// for `input` in every one of its
+/// [`input_formats`], panicking with the name of
+/// each variant and format that doesn't
+pub fn assert_variants<T, E>(
+    variants: &[Variant<T, E>],
+    input: &str,
+    expected: T,
+) where
+    T: PartialEq + Debug,
+    E: Display,
+{
+    check(
+        variants,
+        &input_formats(input),
+        &expected,
+    );
+}
+
+fn check<T, E>(
+    variants: &[Variant<T, E>],
+    inputs: &[(&str, String)],
+    expected: &T,
+) where
+    T: PartialEq + Debug,
+    E: Display,
+{
+    let failures = variants
+        .iter()
+        .flat_map(|variant| {
+            inputs.iter().filter_map(move |(format, input)| {
+                match (variant.process)(input) {
+                    Ok(answer) if answer == *expected => {
+                        None
+                    }
+                    Ok(answer) => Some(format!(
+                        "{variant} ({format}): expected {expected:?}, got {answer:?}"
+                    )),
+                    Err(error) => Some(format!(
+                        "{variant} ({format}): {error}"
+                    )),
+                }
+            })
+        })
+        .collect::<Vec<String>>();
+    assert!(failures.is_empty(), "{failures:#?}");
+}
+
+/// Check every variant against the first one on a
+/// day's real input, loaded with
+/// [`load_day`](crate::input::load_day). Does
+/// nothing when the input is missing or can't be
+/// decrypted, so tests still pass without the
+/// input key.
+pub fn assert_variants_agree_on_input<T, E>(
+    variants: &[Variant<T, E>],
+    day: &str,
+) where
+    T: PartialEq + Debug,
+    E: Display,
+{
+    let Ok(input) = crate::input::load_day(day) else {
+        return;
+    };
+    let Some(first) = variants.first() else {
+        return;
+    };
+    let expected = (first.process)(&input)
+        .unwrap_or_else(|error| panic!("{first}: {error}"));
+    check(
+        variants,
+        &[("the input", input)],
+        &expected,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VARIANTS: &[Variant<usize, String>] = &[
+        Variant {
+            name: "len",
+            process: |input| Ok(input.trim_end().len()),
+        },
+        Variant {
+            name: "count",
+            process: |input| {
+                Ok(input.trim_end().chars().count())
+            },
+        },
+    ];
+
+    #[test]
+    fn test_variants_agree() {
+        assert_variants(VARIANTS, "abc", 3);
+        assert_eq!("count", VARIANTS[1].to_string());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "count (crlf): expected 2, got 1"
+    )]
+    fn test_reports_disagreeing_variant() {
+        assert_variants(VARIANTS, "é", 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "len (final newline)")]
+    fn test_reports_format() {
+        const LEN: &[Variant<usize, String>] = &[Variant {
+            name: "len",
+            process: |input| Ok(input.len()),
+        }];
+        assert_variants(LEN, "abc", 3);
+    }
+}
@@ -0,0 +1,512 @@
+//! Frame-by-frame visualisations of a solution.
+//!
+//! A day implements [`Visualize`] to turn its
+//! puzzle state into [`Frame`]s, grids of
+//! coloured characters with highlighted cells and
+//! caption lines. The frames can be played in the
+//! terminal, printed as plain text or written to
+//! an animated GIF, and [`main`] does whichever
+//! the command line asks for so a day's
+//! `visualize` binary is a couple of lines.
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    thread,
+    time::Duration,
+};
+
+use clap::Parser;
+use miette::Diagnostic;
+use thiserror::Error;
+
+#[derive(Error, Diagnostic, Debug)]
+pub enum VisualizeError {
+    #[error(transparent)]
+    #[diagnostic(code(aoc::io_error))]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    #[diagnostic(code(aoc::gif_error))]
+    Gif(#[from] gif::EncodingError),
+
+    #[error("a {width}x{height} pixel frame is too large for a GIF")]
+    #[diagnostic(
+        code(aoc::frame_too_large),
+        help("use a smaller `--scale`")
+    )]
+    TooLarge { width: usize, height: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const DIM: Rgb = Rgb(70, 70, 70);
+    pub const WHITE: Rgb = Rgb(230, 230, 230);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(95, 175, 0);
+    pub const YELLOW: Rgb = Rgb(255, 215, 0);
+    pub const BLUE: Rgb = Rgb(38, 90, 180);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: Rgb,
+    /// Marked cells are drawn on this colour
+    pub bg: Option<Rgb>,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            ch: ' ',
+            fg: Rgb::WHITE,
+            bg: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    captions: Vec<String>,
+    /// How long the frame is shown
+    pub delay: Duration,
+}
+
+impl Frame {
+    pub const DEFAULT_DELAY: Duration =
+        Duration::from_millis(500);
+
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); width * height],
+            captions: vec![],
+            delay: Self::DEFAULT_DELAY,
+        }
+    }
+
+    /// A frame holding the text of a puzzle grid,
+    /// `.` drawn dimmed
+    pub fn from_grid(grid: &str) -> Self {
+        let lines = grid.lines().collect::<Vec<&str>>();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or_default();
+        let mut frame = Self::new(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                let fg = if ch == '.' {
+                    Rgb::DIM
+                } else {
+                    Rgb::WHITE
+                };
+                frame.set(x, y, ch, fg);
+            }
+        }
+        frame
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&Cell> {
+        (x < self.width && y < self.height)
+            .then(|| &self.cells[y * self.width + x])
+    }
+
+    fn get_mut(
+        &mut self,
+        x: usize,
+        y: usize,
+    ) -> Option<&mut Cell> {
+        (x < self.width && y < self.height)
+            .then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// Cells outside the frame are ignored
+    pub fn set(
+        &mut self,
+        x: usize,
+        y: usize,
+        ch: char,
+        fg: Rgb,
+    ) {
+        if let Some(cell) = self.get_mut(x, y) {
+            cell.ch = ch;
+            cell.fg = fg;
+        }
+    }
+
+    /// Recolour a cell's character
+    pub fn colour(&mut self, x: usize, y: usize, fg: Rgb) {
+        if let Some(cell) = self.get_mut(x, y) {
+            cell.fg = fg;
+        }
+    }
+
+    /// Highlight a cell with a background colour
+    pub fn mark(&mut self, x: usize, y: usize, bg: Rgb) {
+        if let Some(cell) = self.get_mut(x, y) {
+            cell.bg = Some(bg);
+        }
+    }
+
+    /// Add a line of text shown under the grid in
+    /// the terminal and plain text output. GIFs
+    /// only show the grid.
+    pub fn caption(&mut self, text: impl Into<String>) {
+        self.captions.push(text.into());
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The characters and captions without colour
+    pub fn to_ascii(&self) -> String {
+        let mut text = String::new();
+        for row in self.rows() {
+            text.extend(row.iter().map(|cell| cell.ch));
+            text.push('\n');
+        }
+        for caption in &self.captions {
+            text.push_str(caption);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// The frame with 24-bit colour escape codes
+    pub fn to_ansi(&self) -> String {
+        let mut text = String::new();
+        for row in self.rows() {
+            for cell in row {
+                let Rgb(r, g, b) = cell.fg;
+                text.push_str(&format!(
+                    "\x1b[38;2;{r};{g};{b}m"
+                ));
+                if let Some(Rgb(r, g, b)) = cell.bg {
+                    text.push_str(&format!(
+                        "\x1b[48;2;{r};{g};{b}m"
+                    ));
+                }
+                text.push(cell.ch);
+                text.push_str("\x1b[0m");
+            }
+            text.push('\n');
+        }
+        for caption in &self.captions {
+            text.push_str(caption);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// RGB pixels with each cell drawn as a
+    /// `scale`-pixel square on its background,
+    /// the character as a smaller square in
+    /// the middle
+    fn pixels(&self, scale: usize) -> Vec<u8> {
+        let margin = scale / 4;
+        let inner = margin..scale - margin;
+        let mut pixels = Vec::with_capacity(
+            self.cells.len() * scale * scale * 3,
+        );
+        for row in self.rows() {
+            for py in 0..scale {
+                for cell in row {
+                    for px in 0..scale {
+                        let Rgb(r, g, b) = if !cell
+                            .ch
+                            .is_whitespace()
+                            && inner.contains(&px)
+                            && inner.contains(&py)
+                        {
+                            cell.fg
+                        } else {
+                            cell.bg.unwrap_or(Rgb::BLACK)
+                        };
+                        pixels.extend([r, g, b]);
+                    }
+                }
+            }
+        }
+        pixels
+    }
+}
+
+pub trait Visualize {
+    fn frames(&self) -> Vec<Frame>;
+}
+
+/// Play the frames in place, redrawing the screen
+/// for each one
+pub fn play(
+    frames: &[Frame],
+    out: &mut impl Write,
+) -> io::Result<()> {
+    for frame in frames {
+        write!(out, "\x1b[2J\x1b[H{}", frame.to_ansi())?;
+        out.flush()?;
+        thread::sleep(frame.delay);
+    }
+    Ok(())
+}
+
+/// Every frame as plain text, separated by blank
+/// lines
+pub fn write_ascii(
+    frames: &[Frame],
+    out: &mut impl Write,
+) -> io::Result<()> {
+    for (i, frame) in frames.iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        write!(out, "{}", frame.to_ascii())?;
+    }
+    Ok(())
+}
+
+/// An animated GIF that loops forever, sized to
+/// the largest frame
+pub fn write_gif(
+    frames: &[Frame],
+    out: impl Write,
+    scale: usize,
+) -> Result<(), VisualizeError> {
+    let pixel_size = |frame: &Frame| {
+        let (width, height) = (
+            frame.width * scale,
+            frame.height * scale,
+        );
+        match (
+            u16::try_from(width),
+            u16::try_from(height),
+        ) {
+            (Ok(w), Ok(h)) => Ok((w, h)),
+            _ => Err(VisualizeError::TooLarge {
+                width,
+                height,
+            }),
+        }
+    };
+    let (width, height) = frames.iter().try_fold(
+        (1, 1),
+        |(width, height): (u16, u16), frame| {
+            let (w, h) = pixel_size(frame)?;
+            Ok::<_, VisualizeError>((
+                width.max(w),
+                height.max(h),
+            ))
+        },
+    )?;
+    let mut encoder =
+        gif::Encoder::new(out, width, height, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    for frame in frames {
+        let (w, h) = pixel_size(frame)?;
+        if w == 0 || h == 0 {
+            continue;
+        }
+        let mut gif_frame = gif::Frame::from_rgb_speed(
+            w,
+            h,
+            &frame.pixels(scale),
+            10,
+        );
+        // hundredths of a second
+        gif_frame.delay =
+            u16::try_from(frame.delay.as_millis() / 10)
+                .unwrap_or(u16::MAX);
+        gif_frame.dispose = gif::DisposalMethod::Background;
+        encoder.write_frame(&gif_frame)?;
+    }
+    Ok(())
+}
+
+/// Play the visualisation in the terminal, or
+/// export it
+#[derive(Parser, Debug, PartialEq)]
+#[clap(version)]
+struct Args {
+    /// print the frames as plain text
+    #[clap(long, conflicts_with = "gif")]
+    ascii: bool,
+    /// write an animated GIF to this path
+    #[clap(long)]
+    gif: Option<PathBuf>,
+    /// pixels per cell in the GIF
+    #[clap(long, default_value_t = 4)]
+    scale: usize,
+    /// milliseconds per frame, instead of each
+    /// frame's own delay
+    #[clap(long)]
+    delay: Option<u64>,
+}
+
+/// Show the visualisation as the command line
+/// asks: played in the terminal by default,
+/// `--ascii` for plain text or `--gif <path>` for
+/// an animation, with `--scale` pixels per cell
+/// and `--delay` milliseconds per frame
+pub fn main(
+    visualization: &impl Visualize,
+) -> Result<(), VisualizeError> {
+    let args = Args::parse();
+    let mut frames = visualization.frames();
+    if let Some(delay) = args.delay {
+        for frame in &mut frames {
+            frame.delay = Duration::from_millis(delay);
+        }
+    }
+    let stdout = &mut io::stdout().lock();
+    match (args.ascii, args.gif) {
+        (_, Some(path)) => {
+            let file = BufWriter::new(File::create(&path)?);
+            write_gif(&frames, file, args.scale)?;
+            eprintln!(
+                "wrote {} frames to `{}`",
+                frames.len(),
+                path.display()
+            );
+        }
+        (true, None) => write_ascii(&frames, stdout)?,
+        (false, None) => play(&frames, stdout)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_grid() {
+        let mut frame = Frame::from_grid("#.\n.#");
+        frame.mark(1, 0, Rgb::BLUE);
+        frame.caption("2 galaxies");
+        assert_eq!((2, 2), (frame.width(), frame.height()));
+        assert_eq!(
+            Some(Rgb::BLUE),
+            frame.get(1, 0).unwrap().bg
+        );
+        assert_eq!(Rgb::DIM, frame.get(1, 0).unwrap().fg);
+        assert_eq!(
+            "#.\n.#\n2 galaxies\n",
+            frame.to_ascii()
+        );
+        // outside the frame
+        frame.mark(5, 5, Rgb::RED);
+        assert_eq!(None, frame.get(5, 5));
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        let mut frame = Frame::new(1, 1);
+        frame.set(0, 0, '#', Rgb::RED);
+        frame.mark(0, 0, Rgb::BLUE);
+        assert_eq!(
+            "\x1b[38;2;220;50;47m\x1b[48;2;38;90;180m#\x1b[0m\n",
+            frame.to_ansi()
+        );
+    }
+
+    #[test]
+    fn test_pixels() {
+        let mut frame = Frame::new(2, 1);
+        frame.set(0, 0, '#', Rgb::RED);
+        frame.mark(1, 0, Rgb::BLUE);
+        let pixels = frame.pixels(4);
+        assert_eq!(2 * 4 * 4 * 3, pixels.len());
+        let pixel = |x: usize, y: usize| {
+            let i = (y * 8 + x) * 3;
+            Rgb(pixels[i], pixels[i + 1], pixels[i + 2])
+        };
+        assert_eq!(Rgb::BLACK, pixel(0, 0));
+        assert_eq!(Rgb::RED, pixel(1, 1));
+        assert_eq!(Rgb::BLUE, pixel(5, 1));
+    }
+
+    #[test]
+    fn test_write_gif() -> Result<(), VisualizeError> {
+        let frames = [
+            Frame::from_grid("#.\n.#"),
+            Frame::from_grid("#..\n..#"),
+        ];
+        let mut gif = vec![];
+        write_gif(&frames, &mut gif, 2)?;
+        assert!(gif.starts_with(b"GIF89a"));
+        // logical screen of the largest frame
+        assert_eq!([6, 0, 4, 0], gif[6..10]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_gif_too_large() {
+        let frames = [Frame::new(20_000, 1)];
+        assert!(matches!(
+            write_gif(&frames, io::sink(), 4),
+            Err(VisualizeError::TooLarge {
+                width: 80_000,
+                height: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn test_args() {
+        let args = |args: &[&str]| {
+            Args::try_parse_from(
+                ["visualize"].iter().chain(args),
+            )
+        };
+        assert_eq!(
+            Args {
+                ascii: false,
+                gif: None,
+                scale: 4,
+                delay: None,
+            },
+            args(&[]).unwrap()
+        );
+        assert_eq!(
+            Args {
+                ascii: false,
+                gif: Some(PathBuf::from("day.gif")),
+                scale: 8,
+                delay: Some(100),
+            },
+            args(&[
+                "--gif", "day.gif", "--scale", "8",
+                "--delay", "100"
+            ])
+            .unwrap()
+        );
+        assert!(args(&["--gif"]).is_err());
+        assert!(args(&["--fps", "3"]).is_err());
+        assert!(
+            args(&["--ascii", "--gif", "day.gif"]).is_err()
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Known-correct answers, recorded in
+//! `inputs/<user>/answers.toml` next to each
+//! user's inputs as
+//!
+//! ```toml
+//! [day-01]
+//! part1 = "142"
+//! ```
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use miette::IntoDiagnostic;
+
+use crate::users::User;
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Debug, Default)]
+pub struct Answers {
+    path: PathBuf,
+    days: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Answers {
+    /// Answers for the default user's inputs
+    pub fn load() -> miette::Result<Self> {
+        Self::load_for(&User::default())
+    }
+
+    pub fn load_for(user: &User) -> miette::Result<Self> {
+        let path = user.answers_path();
+        let days = if path.exists() {
+            let contents = fs::read_to_string(&path)
+                .into_diagnostic()?;
+            Self::parse(&contents)?.days
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self { path, days })
+    }
+
+    pub fn parse(contents: &str) -> miette::Result<Self> {
+        let days =
+            toml::from_str(contents).into_diagnostic()?;
+        Ok(Self {
+            path: PathBuf::new(),
+            days,
+        })
+    }
+
+    pub fn save(&self) -> miette::Result<()> {
+        let contents = toml::to_string(&self.days)
+            .into_diagnostic()?;
+        fs::write(&self.path, contents).into_diagnostic()
+    }
+
+    pub fn get(
+        &self,
+        day: &str,
+        part: usize,
+    ) -> Option<&str> {
+        self.days
+            .get(day)?
+            .get(&format!("part{part}"))
+            .map(String::as_str)
+    }
+
+    pub fn set(
+        &mut self,
+        day: &str,
+        part: usize,
+        answer: String,
+    ) {
+        self.days
+            .entry(day.to_string())
+            .or_default()
+            .insert(format!("part{part}"), answer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> miette::Result<()> {
+        let answers = Answers::parse(
+            "[day-01]
+part1 = \"142\"
+part2 = \"281\"
+
+[day-02]
+part1 = \"8\"",
+        )?;
+        assert_eq!(Some("142"), answers.get("day-01", 1));
+        assert_eq!(Some("281"), answers.get("day-01", 2));
+        assert_eq!(Some("8"), answers.get("day-02", 1));
+        assert_eq!(None, answers.get("day-02", 2));
+        assert_eq!(None, answers.get("day-03", 1));
+        Ok(())
+    }
+}
@@ -0,0 +1,341 @@
+//! Answers from previous runs, keyed by the day,
+//! the part, a hash of the input, a hash of the
+//! sources the solution was built from and the
+//! [`build`] running it, so that an answer is
+//! only reused when re-running it would give the
+//! same result.
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use aoc_common::workspace_root;
+use clap::Subcommand;
+use miette::IntoDiagnostic;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    days::DAYS,
+    users::{self, User},
+};
+
+pub const CACHE_FILE: &str = ".aoc-cache.json";
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Remove answers whose input or solution
+    /// source has changed since they were cached
+    Prune,
+    /// Remove every cached answer
+    Clear,
+}
+
+pub fn run(command: Command) -> miette::Result<()> {
+    let mut cache = Cache::load()?;
+    let before = cache.entries.len();
+    match command {
+        Command::Prune => {
+            let users = users::all()?;
+            let mut sources = BTreeMap::new();
+            cache.entries.retain(|entry| {
+                let source_hash = sources
+                    .entry(entry.day.clone())
+                    .or_insert_with(|| {
+                        source_hash(&entry.day).ok()
+                    });
+                source_hash.as_ref()
+                    == Some(&entry.source_hash)
+                    && input_hash_matches(entry, &users)
+            });
+        }
+        Command::Clear => cache.entries.clear(),
+    }
+    cache.save()?;
+    println!(
+        "removed {} of {before} cached answers",
+        before - cache.entries.len()
+    );
+    Ok(())
+}
+
+#[derive(
+    Serialize, Deserialize, Debug, Clone, PartialEq,
+)]
+pub struct Entry {
+    pub day: String,
+    pub part: usize,
+    pub input_hash: String,
+    pub source_hash: String,
+    /// See [`build`], empty for entries cached
+    /// before the build was recorded
+    #[serde(default)]
+    pub build: String,
+    pub answer: String,
+    pub duration_ms: f64,
+}
+
+impl Entry {
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.duration_ms / 1000.0)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Cache {
+    entries: Vec<Entry>,
+}
+
+impl Cache {
+    fn path() -> PathBuf {
+        workspace_root().join(CACHE_FILE)
+    }
+
+    pub fn load() -> miette::Result<Self> {
+        let path = Self::path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents =
+            fs::read_to_string(path).into_diagnostic()?;
+        serde_json::from_str(&contents).into_diagnostic()
+    }
+
+    pub fn save(&self) -> miette::Result<()> {
+        let contents = serde_json::to_string_pretty(self)
+            .into_diagnostic()?;
+        fs::write(Self::path(), contents).into_diagnostic()
+    }
+
+    pub fn get(
+        &self,
+        day: &str,
+        part: usize,
+        input_hash: &str,
+        source_hash: &str,
+    ) -> Option<&Entry> {
+        self.entries.iter().find(|entry| {
+            entry.day == day
+                && entry.part == part
+                && entry.input_hash == input_hash
+                && entry.source_hash == source_hash
+                && entry.build == build()
+        })
+    }
+
+    /// The most recently cached answer for a
+    /// part, whether or not it is still
+    /// current
+    pub fn latest(
+        &self,
+        day: &str,
+        part: usize,
+    ) -> Option<&Entry> {
+        self.entries.iter().rev().find(|entry| {
+            entry.day == day && entry.part == part
+        })
+    }
+
+    pub fn insert(&mut self, entry: Entry) {
+        self.entries.retain(|cached| {
+            (
+                &cached.day,
+                cached.part,
+                &cached.input_hash,
+                &cached.source_hash,
+                &cached.build,
+            ) != (
+                &entry.day,
+                entry.part,
+                &entry.input_hash,
+                &entry.source_hash,
+                &entry.build,
+            )
+        });
+        self.entries.push(entry);
+    }
+}
+
+/// The profile and features this binary was built
+/// with, such as `release+checked-math`. A
+/// checked build fails where an unchecked one may
+/// wrap, so their answers are cached separately.
+pub fn build() -> String {
+    let profile = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    };
+    if cfg!(feature = "checked-math") {
+        format!("{profile}+checked-math")
+    } else {
+        profile.to_string()
+    }
+}
+
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Hash everything a day's solutions are built
+/// from: its manifest and `src`, plus the shared
+/// `aoc-common` crate.
+pub fn source_hash(day: &str) -> miette::Result<String> {
+    let root = workspace_root();
+    let mut files = vec![root.join(day).join("Cargo.toml")];
+    collect_files(&root.join(day).join("src"), &mut files)?;
+    collect_files(
+        &root.join("aoc-common/src"),
+        &mut files,
+    )?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        let relative =
+            file.strip_prefix(&root).unwrap_or(&file);
+        hasher
+            .update(relative.to_string_lossy().as_bytes());
+        hasher.update(fs::read(&file).into_diagnostic()?);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn collect_files(
+    dir: &Path,
+    files: &mut Vec<PathBuf>,
+) -> miette::Result<()> {
+    for entry in fs::read_dir(dir).into_diagnostic()? {
+        let path = entry.into_diagnostic()?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Whether the entry was computed from any user's
+/// current input
+fn input_hash_matches(
+    entry: &Entry,
+    users: &[User],
+) -> bool {
+    DAYS.iter().any(|day| day.name == entry.day)
+        && users.iter().any(|user| {
+            user.input(&entry.day).is_ok_and(|input| {
+                hash_bytes(input.as_bytes())
+                    == entry.input_hash
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(answer: &str, source_hash: &str) -> Entry {
+        Entry {
+            day: "day-01".to_string(),
+            part: 1,
+            input_hash: hash_bytes(b"1abc2"),
+            source_hash: source_hash.to_string(),
+            build: build(),
+            answer: answer.to_string(),
+            duration_ms: 1.5,
+        }
+    }
+
+    #[test]
+    fn test_get_matches_every_key_field() {
+        let mut cache = Cache::default();
+        cache.insert(entry("12", "a"));
+        let input_hash = hash_bytes(b"1abc2");
+        assert_eq!(
+            Some("12"),
+            cache
+                .get("day-01", 1, &input_hash, "a")
+                .map(|entry| entry.answer.as_str())
+        );
+        assert!(cache
+            .get("day-01", 2, &input_hash, "a")
+            .is_none());
+        assert!(cache
+            .get("day-01", 1, &input_hash, "b")
+            .is_none());
+        assert!(cache
+            .get("day-01", 1, &hash_bytes(b"1abc3"), "a")
+            .is_none());
+    }
+
+    #[test]
+    fn test_get_ignores_other_builds() {
+        let mut cache = Cache::default();
+        cache.insert(Entry {
+            build: "flamegraph".to_string(),
+            ..entry("12", "a")
+        });
+        cache.insert(Entry {
+            build: String::new(),
+            ..entry("13", "a")
+        });
+        assert_eq!(2, cache.entries.len());
+        assert!(cache
+            .get("day-01", 1, &hash_bytes(b"1abc2"), "a")
+            .is_none());
+        cache.insert(entry("14", "a"));
+        assert_eq!(
+            Some("14"),
+            cache
+                .get(
+                    "day-01",
+                    1,
+                    &hash_bytes(b"1abc2"),
+                    "a"
+                )
+                .map(|entry| entry.answer.as_str())
+        );
+    }
+
+    #[test]
+    fn test_insert_replaces_same_key() {
+        let mut cache = Cache::default();
+        cache.insert(entry("12", "a"));
+        cache.insert(entry("13", "a"));
+        cache.insert(entry("14", "b"));
+        assert_eq!(2, cache.entries.len());
+        assert_eq!(
+            Some("14"),
+            cache
+                .latest("day-01", 1)
+                .map(|entry| entry.answer.as_str())
+        );
+        assert_eq!(
+            Some("13"),
+            cache
+                .get(
+                    "day-01",
+                    1,
+                    &hash_bytes(b"1abc2"),
+                    "a"
+                )
+                .map(|entry| entry.answer.as_str())
+        );
+    }
+
+    #[test]
+    fn test_source_hash_is_stable() -> miette::Result<()> {
+        assert_eq!(
+            source_hash("day-01")?,
+            source_hash("day-01")?
+        );
+        assert_ne!(
+            source_hash("day-01")?,
+            source_hash("day-02")?
+        );
+        Ok(())
+    }
+}
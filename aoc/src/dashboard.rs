@@ -0,0 +1,650 @@
+//! A terminal dashboard for the whole calendar,
+//! showing every day crate and part alongside its
+//! test status, answers and benchmark timings.
+use std::{fs, path::Path, process::Command};
+
+use aoc_common::workspace_root;
+use miette::IntoDiagnostic;
+use ratatui::{
+    crossterm::event::{
+        self, Event, KeyCode, KeyEventKind,
+    },
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{
+        Block, Cell, Clear, Paragraph, Row, Table,
+        TableState, Wrap,
+    },
+    DefaultTerminal, Frame,
+};
+
+use crate::{
+    answers::Answers,
+    cache::Cache,
+    days, run,
+    users::User,
+    watch::{self, TestRun},
+};
+
+/// Benchmark results written by `just bench-all`
+const BENCHMARKS_FILE: &str = "benchmarks.txt";
+
+/// How many log lines the trace summary keeps
+const TRACE_TAIL: usize = 30;
+
+const HELP: &str =
+    "↑/↓ select  r run  t test  s trace summary  q quit";
+
+const EMPTY: &str =
+    "no day-* crates yet, create one with `aoc new`  q quit";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tests {
+    NotRun,
+    Passed(usize),
+    Failed,
+}
+
+#[derive(Debug)]
+struct PartRow {
+    day: String,
+    part: usize,
+    implemented: bool,
+    tests: Tests,
+    last_answer: Option<String>,
+    recorded: Option<String>,
+    bench: Option<String>,
+}
+
+struct App {
+    rows: Vec<PartRow>,
+    table: TableState,
+    message: String,
+    popup: Option<(String, String)>,
+}
+
+pub fn run() -> miette::Result<()> {
+    let mut app = App::load()?;
+    let mut terminal = ratatui::init();
+    let result = app.event_loop(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl App {
+    fn load() -> miette::Result<Self> {
+        let root = workspace_root();
+        let answers = Answers::load()?;
+        let cache = Cache::load()?;
+        let mut days = fs::read_dir(&root)
+            .into_diagnostic()?
+            .filter_map(|entry| {
+                let name = entry
+                    .ok()?
+                    .file_name()
+                    .into_string()
+                    .ok()?;
+                name.starts_with("day-").then_some(name)
+            })
+            .collect::<Vec<String>>();
+        days.sort();
+
+        let rows = days
+            .iter()
+            .flat_map(|day| {
+                [1, 2].map(|part| PartRow {
+                    day: day.clone(),
+                    part,
+                    implemented: is_implemented(
+                        &root.join(day).join(format!(
+                            "src/part{part}.rs"
+                        )),
+                    ),
+                    tests: Tests::NotRun,
+                    last_answer: cache
+                        .latest(day, part)
+                        .map(|entry| entry.answer.clone()),
+                    recorded: answers
+                        .get(day, part)
+                        .map(str::to_string),
+                    bench: last_bench(&root, day, part),
+                })
+            })
+            .collect::<Vec<_>>();
+        let message =
+            if rows.is_empty() { EMPTY } else { HELP };
+        Ok(Self {
+            table: TableState::default().with_selected(
+                (!rows.is_empty()).then_some(0),
+            ),
+            rows,
+            message: message.to_string(),
+            popup: None,
+        })
+    }
+
+    fn event_loop(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+    ) -> miette::Result<()> {
+        loop {
+            terminal
+                .draw(|frame| self.draw(frame))
+                .into_diagnostic()?;
+            let Event::Key(key) =
+                event::read().into_diagnostic()?
+            else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if self.popup.is_some() {
+                self.popup = None;
+                continue;
+            }
+            let action: fn(&mut Self) = match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    return Ok(())
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.table.select_next();
+                    continue;
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.table.select_previous();
+                    continue;
+                }
+                KeyCode::Char('r') => Self::run_selected,
+                KeyCode::Char('t') => Self::test_selected,
+                KeyCode::Char('s') => Self::trace_selected,
+                _ => continue,
+            };
+            if self.rows.is_empty() {
+                continue;
+            }
+            // running blocks the loop, show what's
+            // happening first
+            self.message = "running...".to_string();
+            terminal
+                .draw(|frame| self.draw(frame))
+                .into_diagnostic()?;
+            action(self);
+        }
+    }
+
+    /// `None` when there are no days to select
+    fn selected(&mut self) -> Option<&mut PartRow> {
+        let last = self.rows.len().checked_sub(1)?;
+        let index = self
+            .table
+            .selected()
+            .unwrap_or_default()
+            .min(last);
+        self.rows.get_mut(index)
+    }
+
+    fn run_selected(&mut self) {
+        let Some(row) = self.selected() else {
+            return;
+        };
+        let Some(day) = days::find(&row.day) else {
+            self.message = format!(
+                "{} isn't registered with `aoc`",
+                row.day
+            );
+            return;
+        };
+        let user = User::default();
+        // `run_parts` silences the panic hook
+        // `ratatui::init` installed, so a `todo!()`
+        // part doesn't restore the terminal
+        let Some(result) = run::run_parts(
+            &[run::Job {
+                day,
+                part: row.part,
+                user: &user,
+                expected: row.recorded.clone(),
+            }],
+            None,
+            false,
+        )
+        .pop() else {
+            return;
+        };
+        row.last_answer.clone_from(&result.answer);
+        let message = match &result.error {
+            Some(error) => format!(
+                "{} part{}: {error}",
+                row.day, row.part
+            ),
+            None => format!(
+                "{} part{}: {} in {:.2?}",
+                row.day,
+                row.part,
+                result
+                    .answer
+                    .as_deref()
+                    .unwrap_or_default(),
+                result.duration
+            ),
+        };
+        self.message = message;
+        if let Ok(mut cache) = Cache::load() {
+            run::cache_result(&mut cache, &result);
+            if let Err(error) = cache.save() {
+                self.message = error.to_string();
+            }
+        }
+    }
+
+    fn test_selected(&mut self) {
+        let Some(row) = self.selected() else {
+            return;
+        };
+        match watch::run_tests(&row.day, row.part) {
+            Ok(TestRun {
+                success: true,
+                passed,
+                ..
+            }) => {
+                row.tests = Tests::Passed(passed);
+                self.message = HELP.to_string();
+            }
+            Ok(TestRun { output, .. }) => {
+                row.tests = Tests::Failed;
+                let title = format!(
+                    "{} part{} tests",
+                    row.day, row.part
+                );
+                self.popup = Some((title, output));
+            }
+            Err(error) => {
+                self.message = error.to_string();
+            }
+        }
+    }
+
+    fn trace_selected(&mut self) {
+        let Some(row) = self.selected() else {
+            return;
+        };
+        let title =
+            format!("{} part{} trace", row.day, row.part);
+        let summary =
+            match trace_summary(&row.day, row.part) {
+                Ok(summary) => summary,
+                Err(error) => error,
+            };
+        self.message = HELP.to_string();
+        self.popup = Some((title, summary));
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [table_area, message_area] =
+            Layout::vertical([
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .areas(frame.area());
+
+        let header = Row::new([
+            "day",
+            "part",
+            "impl",
+            "tests",
+            "last answer",
+            "recorded",
+            "bench",
+        ])
+        .style(Style::new().add_modifier(Modifier::BOLD));
+        let rows = self.rows.iter().map(|row| {
+            let tests =
+                match row.tests {
+                    Tests::NotRun => Cell::from("-"),
+                    Tests::Passed(passed) => Cell::from(
+                        format!("{passed} passed"),
+                    )
+                    .style(Style::new().fg(Color::Green)),
+                    Tests::Failed => Cell::from("FAILED")
+                        .style(Style::new().fg(Color::Red)),
+                };
+            let answer_style =
+                match (&row.last_answer, &row.recorded) {
+                    (Some(answer), Some(recorded))
+                        if answer == recorded =>
+                    {
+                        Style::new().fg(Color::Green)
+                    }
+                    (Some(_), Some(_)) => {
+                        Style::new().fg(Color::Red)
+                    }
+                    _ => Style::new(),
+                };
+            Row::new([
+                Cell::from(row.day.as_str()),
+                Cell::from(format!("part{}", row.part)),
+                Cell::from(if row.implemented {
+                    "yes"
+                } else {
+                    "todo"
+                }),
+                tests,
+                Cell::from(
+                    row.last_answer
+                        .clone()
+                        .unwrap_or_default(),
+                )
+                .style(answer_style),
+                Cell::from(
+                    row.recorded
+                        .clone()
+                        .unwrap_or_default(),
+                ),
+                Cell::from(
+                    row.bench.clone().unwrap_or_default(),
+                ),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(7),
+                Constraint::Length(6),
+                Constraint::Length(5),
+                Constraint::Length(10),
+                Constraint::Min(16),
+                Constraint::Min(16),
+                Constraint::Length(12),
+            ],
+        )
+        .header(header)
+        .block(Block::bordered().title("advent of code"))
+        .row_highlight_style(
+            Style::new().add_modifier(Modifier::REVERSED),
+        );
+        frame.render_stateful_widget(
+            table,
+            table_area,
+            &mut self.table,
+        );
+        frame.render_widget(
+            Line::from(self.message.as_str()),
+            message_area,
+        );
+
+        if let Some((title, text)) = &self.popup {
+            let area = centered(frame.area());
+            frame.render_widget(Clear, area);
+            frame.render_widget(
+                Paragraph::new(text.as_str())
+                    .wrap(Wrap { trim: false })
+                    .block(
+                        Block::bordered()
+                            .title(title.as_str())
+                            .title_bottom(
+                                "press any key to close",
+                            ),
+                    ),
+                area,
+            );
+        }
+    }
+}
+
+fn centered(area: Rect) -> Rect {
+    let [_, area, _] = Layout::vertical([
+        Constraint::Percentage(10),
+        Constraint::Percentage(80),
+        Constraint::Percentage(10),
+    ])
+    .areas(area);
+    let [_, area, _] = Layout::horizontal([
+        Constraint::Percentage(10),
+        Constraint::Percentage(80),
+        Constraint::Percentage(10),
+    ])
+    .areas(area);
+    area
+}
+
+/// A part is implemented once its `process` no
+/// longer contains the template's `todo!()`
+fn is_implemented(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|source| {
+        let solution = source
+            .split_once("#[cfg(test)]")
+            .map_or(source.as_str(), |(solution, _)| {
+                solution
+            });
+        !solution.contains("todo!(")
+    })
+}
+
+/// The median of the most recent divan run for
+/// the part, from `just bench` or `just
+/// bench-all`
+fn last_bench(
+    root: &Path,
+    day: &str,
+    part: usize,
+) -> Option<String> {
+    [format!("{day}.bench.txt"), BENCHMARKS_FILE.into()]
+        .iter()
+        .find_map(|file| {
+            let output =
+                fs::read_to_string(root.join(file)).ok()?;
+            parse_bench(&output, day, part)
+        })
+}
+
+fn parse_bench(
+    output: &str,
+    day: &str,
+    part: usize,
+) -> Option<String> {
+    let section =
+        format!("{}_bench ", day.replace('-', "_"));
+    let name = format!("─ part{part} ");
+    let mut in_section = false;
+    let mut in_part = false;
+    let mut median = None;
+    let mut variants = vec![];
+    for line in output.lines() {
+        if !line.starts_with(['├', '╰', '│', ' ']) {
+            in_section = line.starts_with(&section);
+            in_part = false;
+        } else if !in_section {
+            continue;
+        } else if line.starts_with(['├', '╰']) {
+            in_part = line.contains(&name);
+            if in_part {
+                median = bench_median(line);
+                variants.clear();
+            }
+        } else if in_part {
+            // a variant nested under the part
+            let label = line
+                .trim_start_matches([
+                    '│', ' ', '├', '╰', '─',
+                ])
+                .split_whitespace()
+                .next()
+                .unwrap_or_default();
+            if let Some(variant) = bench_median(line) {
+                variants.push(format!("{label} {variant}"));
+            }
+        }
+    }
+    match median {
+        Some(median) if median.is_empty() => {
+            Some(variants.join(", "))
+        }
+        median => median,
+    }
+}
+
+/// Columns are name+fastest, slowest, median, ...
+/// nested rows are prefixed with their parent's
+/// `│`
+fn bench_median(line: &str) -> Option<String> {
+    line.trim_start_matches(['│', ' '])
+        .split('│')
+        .nth(2)
+        .map(|median| median.trim().to_string())
+}
+
+/// Build and run the part with every tracing
+/// event enabled, then count the events per level
+/// and keep the last few
+fn trace_summary(
+    day: &str,
+    part: usize,
+) -> Result<String, String> {
+    let executable = watch::build(day, part)?;
+    let output = Command::new(executable)
+        .current_dir(workspace_root().join(day))
+        .env("RUST_LOG", "trace")
+        .env("NO_COLOR", "1")
+        .output()
+        .map_err(|error| error.to_string())?;
+    let log = strip_ansi(&format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    ));
+    Ok(summarize(&log))
+}
+
+fn summarize(log: &str) -> String {
+    let levels =
+        ["ERROR", "WARN", "INFO", "DEBUG", "TRACE"];
+    let counts = levels
+        .iter()
+        .map(|level| {
+            let count = log
+                .lines()
+                .filter(|line| {
+                    line.split_whitespace().nth(1)
+                        == Some(level)
+                })
+                .count();
+            format!("{level} {count}")
+        })
+        .collect::<Vec<_>>()
+        .join("  ");
+    let lines = log.lines().collect::<Vec<_>>();
+    let tail =
+        &lines[lines.len().saturating_sub(TRACE_TAIL)..];
+    format!("{counts}\n\n{}", tail.join("\n"))
+}
+
+/// Remove colour codes, in case the subscriber
+/// ignores `NO_COLOR`
+fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars
+                .by_ref()
+                .find(|c| c.is_ascii_alphabetic());
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selected_without_days() {
+        let mut app = App {
+            rows: vec![],
+            table: TableState::default(),
+            message: EMPTY.to_string(),
+            popup: None,
+        };
+        assert!(app.selected().is_none());
+        app.table.select_next();
+        assert!(app.selected().is_none());
+        app.run_selected();
+        assert_eq!(EMPTY, app.message);
+    }
+
+    #[test]
+    fn test_is_implemented() {
+        let root = workspace_root();
+        assert!(is_implemented(
+            &root.join("day-01/src/part1.rs")
+        ));
+        assert!(!is_implemented(
+            &root.join("daily-template/src/part1.rs")
+        ));
+        assert!(!is_implemented(
+            &root.join("day-99/src/part1.rs")
+        ));
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        let output = "running 0 tests
+
+day_01_bench  fastest       │ slowest       │ median        │ mean          │ samples │ iters
+├─ part1      88.99 µs      │ 152.7 µs      │ 92.97 µs      │ 94.6 µs       │ 100     │ 100
+╰─ part2      128.7 ms      │ 137 ms        │ 133 ms        │ 132.9 ms      │ 100     │ 100
+
+day_12_bench    fastest       │ slowest       │ median        │ mean          │ samples │ iters
+├─ part1                      │               │               │               │         │
+│  ├─ memoised  15.45 ms      │ 21.84 ms      │ 15.8 ms       │ 16.15 ms      │ 100     │ 100
+│  ╰─ naive     1.085 ms      │ 1.378 ms      │ 1.105 ms      │ 1.108 ms      │ 100     │ 100
+╰─ part2        20 ms         │ 21 ms         │ 20.5 ms       │ 20.6 ms       │ 100     │ 100
+
+day_02_bench  fastest       │ slowest       │ median        │ mean          │ samples │ iters
+╰─ part1      1 ms          │ 2 ms          │ 1.5 ms        │ 1.5 ms        │ 100     │ 100
+";
+        assert_eq!(
+            Some("92.97 µs".to_string()),
+            parse_bench(output, "day-01", 1)
+        );
+        assert_eq!(
+            Some("133 ms".to_string()),
+            parse_bench(output, "day-01", 2)
+        );
+        assert_eq!(
+            Some("1.5 ms".to_string()),
+            parse_bench(output, "day-02", 1)
+        );
+        assert_eq!(None, parse_bench(output, "day-02", 2));
+        assert_eq!(
+            Some(
+                "memoised 15.8 ms, naive 1.105 ms"
+                    .to_string()
+            ),
+            parse_bench(output, "day-12", 1)
+        );
+        assert_eq!(
+            Some("20.5 ms".to_string()),
+            parse_bench(output, "day-12", 2)
+        );
+    }
+
+    #[test]
+    fn test_summarize() {
+        let log = strip_ansi(
+            "2023-12-01T00:00:00Z \x1b[34mDEBUG\x1b[0m day_04: total number of cards
+2023-12-01T00:00:00Z TRACE day_04: card
+2023-12-01T00:00:00Z TRACE day_04: card
+9425061",
+        );
+        let summary = summarize(&log);
+        assert!(summary.starts_with(
+            "ERROR 0  WARN 0  INFO 0  DEBUG 1  TRACE 2"
+        ));
+        assert!(summary.ends_with("9425061"));
+    }
+}
@@ -0,0 +1,54 @@
+//! Every day crate in the workspace, so the `aoc`
+//! binary can run solutions in process.
+//!
+//! `aoc new` inserts new days above the marker at
+//! the end of [`DAYS`].
+
+/// A part's `process`, with the answer rendered
+/// as a string so days with different answer
+/// types can be listed together.
+pub type Solution = fn(&str) -> miette::Result<String>;
+
+pub struct Day {
+    /// Crate name, e.g. `day-01`, which is also
+    /// the directory holding the day's inputs
+    pub name: &'static str,
+    pub parts: [Solution; 2],
+}
+
+macro_rules! day {
+    ($name:literal, $krate:ident) => {
+        Day {
+            name: $name,
+            parts: [
+                |input| {
+                    Ok($krate::part1::process(input)?
+                        .to_string())
+                },
+                |input| {
+                    Ok($krate::part2::process(input)?
+                        .to_string())
+                },
+            ],
+        }
+    };
+}
+
+pub const DAYS: &[Day] = &[
+    day!("day-01", day_01),
+    day!("day-02", day_02),
+    day!("day-03", day_03),
+    day!("day-04", day_04),
+    day!("day-05", day_05),
+    day!("day-06", day_06),
+    day!("day-11", day_11),
+    day!("day-12", day_12),
+    // aoc new: insert days above this line
+];
+
+pub const MARKER: &str =
+    "    // aoc new: insert days above this line";
+
+pub fn find(name: &str) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.name == name)
+}
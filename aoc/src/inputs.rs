@@ -0,0 +1,124 @@
+use std::{fs, path::Path};
+
+use age::x25519;
+use aoc_common::{input, workspace_root};
+use clap::Subcommand;
+use miette::{miette, Context, IntoDiagnostic};
+
+use crate::users;
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Write a new input key to `.aoc-key` in the
+    /// workspace root
+    Keygen,
+    /// Encrypt every user's plaintext inputs in
+    /// `inputs/<user>` with the configured input
+    /// key, deleting the plaintext copies
+    Encrypt {
+        /// days to encrypt, formatted as
+        /// `day-01`. Defaults to every
+        /// day in the workspace
+        days: Vec<String>,
+        /// keep the plaintext inputs after
+        /// encrypting
+        #[clap(long)]
+        keep: bool,
+    },
+}
+
+pub fn run(command: Command) -> miette::Result<()> {
+    match command {
+        Command::Keygen => keygen(),
+        Command::Encrypt { days, keep } => {
+            encrypt(days, keep)
+        }
+    }
+}
+
+fn keygen() -> miette::Result<()> {
+    let path = workspace_root().join(input::KEY_FILE);
+    if path.exists() {
+        return Err(miette!(
+            "`{}` already exists, refusing to overwrite it",
+            path.display()
+        ));
+    }
+    let (identity, contents) = input::generate_key();
+    fs::write(&path, contents).into_diagnostic()?;
+    println!("wrote `{}`", path.display());
+    println!("public key: {}", identity.to_public());
+    Ok(())
+}
+
+fn encrypt(
+    days: Vec<String>,
+    keep: bool,
+) -> miette::Result<()> {
+    let identity = input::find_key()?.ok_or_else(|| {
+        miette!(
+            help = format!(
+                "run `aoc inputs keygen` or set {}",
+                input::KEY_ENV
+            ),
+            "no input key is configured"
+        )
+    })?;
+    let recipient = identity.to_public();
+    let days =
+        if days.is_empty() { all_days()? } else { days };
+    for day in days {
+        for user in users::all()? {
+            let (dir, name) = user
+                .input_location(&workspace_root(), &day);
+            encrypt_input(
+                &dir.join(name),
+                &recipient,
+                keep,
+            )
+            .wrap_err_with(|| {
+                format!(
+                    "encrypting {day} for {}",
+                    user.name
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Encrypt a plaintext input, skipping missing
+/// and empty files
+pub fn encrypt_input(
+    path: &Path,
+    recipient: &x25519::Recipient,
+    keep: bool,
+) -> miette::Result<()> {
+    if !path.exists()
+        || fs::metadata(path).into_diagnostic()?.len() == 0
+    {
+        return Ok(());
+    }
+    let encrypted = input::encrypt_file(path, recipient)?;
+    if !keep {
+        fs::remove_file(path).into_diagnostic()?;
+    }
+    println!("encrypted `{}`", encrypted.display());
+    Ok(())
+}
+
+fn all_days() -> miette::Result<Vec<String>> {
+    let mut days = fs::read_dir(workspace_root())
+        .into_diagnostic()?
+        .filter_map(|entry| {
+            let name = entry
+                .ok()?
+                .file_name()
+                .into_string()
+                .ok()?;
+            name.starts_with("day-").then_some(name)
+        })
+        .collect::<Vec<String>>();
+    days.sort();
+    Ok(days)
+}
@@ -0,0 +1,602 @@
+//! Statistics for a private leaderboard, read
+//! from the JSON that the leaderboard's "API"
+//! link serves.
+//!
+//! ```json
+//! {"event": "2023", "members": {"1": {
+//!     "id": 1, "name": "ferris", "stars": 2,
+//!     "local_score": 4,
+//!     "completion_day_level": {"1": {
+//!         "1": {"get_star_ts": 1701406900},
+//!         "2": {"get_star_ts": 1701407200}
+//!     }}
+//! }}}
+//! ```
+use std::{
+    collections::BTreeMap, fmt::Write, fs, path::PathBuf,
+};
+
+use clap::{Args, ValueEnum};
+use miette::{Context, IntoDiagnostic};
+use serde::Deserialize;
+
+use crate::table;
+
+/// Puzzles unlock at midnight EST, 05:00 UTC
+const UNLOCK_HOUR_UTC: i64 = 5;
+
+#[derive(Args, Debug)]
+pub struct LeaderboardArgs {
+    /// leaderboard JSON, saved from the private
+    /// leaderboard's API link
+    file: PathBuf,
+    #[clap(long, value_enum, default_value_t = View::Scores)]
+    view: View,
+    /// only show this day in timelines, deltas
+    /// and rankings
+    #[clap(long, value_parser = clap::value_parser!(u32).range(1..=25))]
+    day: Option<u32>,
+    /// also write a chart of stars over time
+    #[clap(long)]
+    svg: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum View {
+    /// local score, recomputed from star times
+    Scores,
+    /// every star each member collected, in order
+    Timeline,
+    /// time between part 1 and part 2
+    Deltas,
+    /// members ranked on each day
+    Rankings,
+}
+
+pub fn run(args: LeaderboardArgs) -> miette::Result<()> {
+    let contents = fs::read_to_string(&args.file)
+        .into_diagnostic()
+        .wrap_err_with(|| {
+            format!("reading `{}`", args.file.display())
+        })?;
+    let leaderboard = Leaderboard::parse(&contents)?;
+    let days = match args.day {
+        Some(day) => vec![day],
+        None => (1..=25).collect(),
+    };
+
+    match args.view {
+        View::Scores => print_scores(&leaderboard),
+        View::Timeline => {
+            print_timeline(&leaderboard, &days)
+        }
+        View::Deltas => print_deltas(&leaderboard, &days),
+        View::Rankings => {
+            print_rankings(&leaderboard, &days)
+        }
+    }
+
+    if let Some(path) = args.svg {
+        fs::write(&path, leaderboard.svg())
+            .into_diagnostic()
+            .wrap_err_with(|| {
+                format!("writing `{}`", path.display())
+            })?;
+    }
+    Ok(())
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Leaderboard {
+    pub event: String,
+    pub members: BTreeMap<String, Member>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u64,
+    /// day -> part -> star
+    pub completion_day_level:
+        BTreeMap<u32, BTreeMap<u32, Star>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Star {
+    pub get_star_ts: i64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StarEvent {
+    pub day: u32,
+    pub part: u32,
+    pub timestamp: i64,
+}
+
+#[derive(Debug)]
+pub struct DayRank<'a> {
+    pub member: &'a Member,
+    /// seconds from the puzzle unlocking
+    pub part1: i64,
+    pub part2: Option<i64>,
+}
+
+impl Member {
+    /// Anonymous members are shown the way the
+    /// leaderboard page shows them
+    pub fn display_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            format!("(anonymous user #{})", self.id)
+        })
+    }
+
+    pub fn star(&self, day: u32, part: u32) -> Option<i64> {
+        self.completion_day_level
+            .get(&day)?
+            .get(&part)
+            .map(|star| star.get_star_ts)
+    }
+
+    /// Every star in the order it was collected
+    pub fn timeline(&self) -> Vec<StarEvent> {
+        let mut events = self
+            .completion_day_level
+            .iter()
+            .flat_map(|(&day, parts)| {
+                parts.iter().map(move |(&part, star)| {
+                    StarEvent {
+                        day,
+                        part,
+                        timestamp: star.get_star_ts,
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        events.sort_by_key(|event| event.timestamp);
+        events
+    }
+
+    /// Seconds between the two stars of a day
+    pub fn delta(&self, day: u32) -> Option<i64> {
+        Some(self.star(day, 2)? - self.star(day, 1)?)
+    }
+}
+
+impl Leaderboard {
+    pub fn parse(contents: &str) -> miette::Result<Self> {
+        serde_json::from_str(contents)
+            .into_diagnostic()
+            .wrap_err("parsing leaderboard json")
+    }
+
+    pub fn year(&self) -> i64 {
+        self.event.parse().unwrap_or(2023)
+    }
+
+    /// Unix timestamp at which a day unlocks
+    pub fn unlock(&self, day: u32) -> i64 {
+        days_from_civil(self.year(), 12, day as i64)
+            * 86_400
+            + UNLOCK_HOUR_UTC * 3600
+    }
+
+    /// Recompute every member's local score: for
+    /// each star, the first member to get it
+    /// scores as many points as there are
+    /// members, the second one less, and so
+    /// on.
+    pub fn local_scores(&self) -> BTreeMap<u64, u64> {
+        let total = self.members.len() as u64;
+        let mut scores = self
+            .members
+            .values()
+            .map(|member| (member.id, 0))
+            .collect::<BTreeMap<u64, u64>>();
+        for day in 1..=25 {
+            for part in 1..=2 {
+                let mut finishers = self
+                    .members
+                    .values()
+                    .filter_map(|member| {
+                        Some((
+                            member.star(day, part)?,
+                            member.id,
+                        ))
+                    })
+                    .collect::<Vec<_>>();
+                finishers.sort();
+                for (rank, (_, id)) in
+                    finishers.into_iter().enumerate()
+                {
+                    *scores.entry(id).or_default() +=
+                        total - rank as u64;
+                }
+            }
+        }
+        scores
+    }
+
+    /// Members who collected a star on the day,
+    /// fastest part 2 first, then those with only
+    /// part 1
+    pub fn day_ranking(
+        &self,
+        day: u32,
+    ) -> Vec<DayRank<'_>> {
+        let unlock = self.unlock(day);
+        let mut ranking = self
+            .members
+            .values()
+            .filter_map(|member| {
+                Some(DayRank {
+                    member,
+                    part1: member.star(day, 1)? - unlock,
+                    part2: member
+                        .star(day, 2)
+                        .map(|ts| ts - unlock),
+                })
+            })
+            .collect::<Vec<_>>();
+        ranking.sort_by_key(|rank| {
+            (
+                rank.part2.is_none(),
+                rank.part2,
+                rank.part1,
+            )
+        });
+        ranking
+    }
+
+    /// A line chart of each member's total stars
+    /// over time
+    pub fn svg(&self) -> String {
+        const WIDTH: f64 = 800.0;
+        const HEIGHT: f64 = 400.0;
+        const MARGIN: f64 = 40.0;
+        const COLOURS: [&str; 8] = [
+            "#e6194b", "#3cb44b", "#4363d8", "#f58231",
+            "#911eb4", "#42d4f4", "#f032e6", "#808000",
+        ];
+
+        let start = self.unlock(1);
+        let end = self
+            .members
+            .values()
+            .flat_map(|member| member.timeline())
+            .map(|event| event.timestamp)
+            .max()
+            .unwrap_or(start)
+            .max(start + 1);
+        let max_stars = self
+            .members
+            .values()
+            .map(|member| member.timeline().len())
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        let x = |ts: i64| {
+            MARGIN
+                + (ts - start) as f64 / (end - start) as f64
+                    * (WIDTH - 2.0 * MARGIN)
+        };
+        let y = |stars: usize| {
+            HEIGHT
+                - MARGIN
+                - stars as f64 / max_stars as f64
+                    * (HEIGHT - 2.0 * MARGIN)
+        };
+
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" font-family="sans-serif" font-size="12">
+<rect width="100%" height="100%" fill="white"/>
+<line x1="{MARGIN}" y1="{bottom}" x2="{right}" y2="{bottom}" stroke="black"/>
+<line x1="{MARGIN}" y1="{MARGIN}" x2="{MARGIN}" y2="{bottom}" stroke="black"/>
+<text x="{MARGIN}" y="{label}">{year} stars over time</text>
+"#,
+            bottom = HEIGHT - MARGIN,
+            right = WIDTH - MARGIN,
+            label = MARGIN / 2.0,
+            year = self.year(),
+        );
+        for (index, member) in
+            self.members.values().enumerate()
+        {
+            let colour = COLOURS[index % COLOURS.len()];
+            let mut points =
+                format!("{},{}", x(start), y(0));
+            for (stars, event) in
+                member.timeline().iter().enumerate()
+            {
+                // step up at each star
+                write!(
+                    points,
+                    " {x},{} {x},{}",
+                    y(stars),
+                    y(stars + 1),
+                    x = x(event.timestamp),
+                )
+                .unwrap();
+            }
+            writeln!(
+                svg,
+                r#"<polyline fill="none" stroke="{colour}" stroke-width="2" points="{points}"/>
+<text x="{}" y="{}" fill="{colour}">{}</text>"#,
+                WIDTH - MARGIN - 150.0,
+                MARGIN + 15.0 * (index + 1) as f64,
+                escape_xml(&member.display_name()),
+            )
+            .unwrap();
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+fn print_scores(leaderboard: &Leaderboard) {
+    let scores = leaderboard.local_scores();
+    let mut members =
+        leaderboard.members.values().collect::<Vec<_>>();
+    members.sort_by_key(|member| {
+        (
+            std::cmp::Reverse(scores[&member.id]),
+            std::cmp::Reverse(member.stars),
+        )
+    });
+    let rows = members
+        .iter()
+        .enumerate()
+        .map(|(rank, member)| {
+            [
+                (rank + 1).to_string(),
+                member.display_name(),
+                member.stars.to_string(),
+                scores[&member.id].to_string(),
+                member.local_score.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    table::print(
+        &["rank", "member", "stars", "score", "reported"],
+        &rows,
+    );
+}
+
+fn print_timeline(leaderboard: &Leaderboard, days: &[u32]) {
+    let rows = leaderboard
+        .members
+        .values()
+        .flat_map(|member| {
+            member
+                .timeline()
+                .into_iter()
+                .enumerate()
+                .filter(|(_, event)| {
+                    days.contains(&event.day)
+                })
+                .map(|(stars, event)| {
+                    [
+                        member.display_name(),
+                        event.day.to_string(),
+                        event.part.to_string(),
+                        format_elapsed(
+                            event.timestamp
+                                - leaderboard
+                                    .unlock(event.day),
+                        ),
+                        (stars + 1).to_string(),
+                    ]
+                })
+        })
+        .collect::<Vec<_>>();
+    table::print(
+        &["member", "day", "part", "after unlock", "stars"],
+        &rows,
+    );
+}
+
+fn print_deltas(leaderboard: &Leaderboard, days: &[u32]) {
+    let rows = days
+        .iter()
+        .flat_map(|&day| {
+            leaderboard.members.values().filter_map(
+                move |member| {
+                    Some([
+                        day.to_string(),
+                        member.display_name(),
+                        format_elapsed(member.delta(day)?),
+                    ])
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    table::print(&["day", "member", "part 1 to 2"], &rows);
+}
+
+fn print_rankings(leaderboard: &Leaderboard, days: &[u32]) {
+    let rows = days
+        .iter()
+        .flat_map(|&day| {
+            leaderboard
+                .day_ranking(day)
+                .into_iter()
+                .enumerate()
+                .map(move |(rank, entry)| {
+                    [
+                        day.to_string(),
+                        (rank + 1).to_string(),
+                        entry.member.display_name(),
+                        format_elapsed(entry.part1),
+                        entry
+                            .part2
+                            .map(format_elapsed)
+                            .unwrap_or_default(),
+                    ]
+                })
+        })
+        .collect::<Vec<_>>();
+    table::print(
+        &["day", "rank", "member", "part 1", "part 2"],
+        &rows,
+    );
+}
+
+/// `h:mm:ss`, hours keep counting past a day
+fn format_elapsed(seconds: i64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// Days since 1970-01-01 for a date in the
+/// proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4
+        - year_of_era / 100
+        + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// day 1 unlocks at 1701406800, day 2 at
+    /// 1701493200
+    const LEADERBOARD: &str = r#"{
+  "owner_id": 1,
+  "event": "2023",
+  "members": {
+    "1": {
+      "id": 1, "name": "ferris", "stars": 4,
+      "local_score": 11, "global_score": 0,
+      "last_star_ts": 1701494000,
+      "completion_day_level": {
+        "1": {
+          "1": {"get_star_ts": 1701406900, "star_index": 1},
+          "2": {"get_star_ts": 1701407500, "star_index": 2}
+        },
+        "2": {
+          "1": {"get_star_ts": 1701493300, "star_index": 5},
+          "2": {"get_star_ts": 1701494000, "star_index": 7}
+        }
+      }
+    },
+    "2": {
+      "id": 2, "name": null, "stars": 3,
+      "local_score": 7, "global_score": 0,
+      "last_star_ts": 1701493800,
+      "completion_day_level": {
+        "1": {
+          "1": {"get_star_ts": 1701406860, "star_index": 0},
+          "2": {"get_star_ts": 1701408000, "star_index": 3}
+        },
+        "2": {
+          "1": {"get_star_ts": 1701493800, "star_index": 6}
+        }
+      }
+    },
+    "3": {
+      "id": 3, "name": "corro", "stars": 0,
+      "local_score": 0, "global_score": 0,
+      "last_star_ts": 0,
+      "completion_day_level": {}
+    }
+  }
+}"#;
+
+    #[test]
+    fn test_unlock() -> miette::Result<()> {
+        let leaderboard = Leaderboard::parse(LEADERBOARD)?;
+        assert_eq!(1701406800, leaderboard.unlock(1));
+        assert_eq!(1701493200, leaderboard.unlock(2));
+        assert_eq!(1703480400, leaderboard.unlock(25));
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_scores() -> miette::Result<()> {
+        let leaderboard = Leaderboard::parse(LEADERBOARD)?;
+        // 3 members: day 1 part 1 2 wins 3 to 2,
+        // part 2 1 wins 3 to 2, day 2 part 1 1 wins
+        // 3 to 2, day 2 part 2 only 1 scores 3
+        assert_eq!(
+            BTreeMap::from([(1, 11), (2, 7), (3, 0)]),
+            leaderboard.local_scores()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_timeline_and_deltas() -> miette::Result<()> {
+        let leaderboard = Leaderboard::parse(LEADERBOARD)?;
+        let anonymous = &leaderboard.members["2"];
+        assert_eq!(
+            "(anonymous user #2)",
+            anonymous.display_name()
+        );
+        assert_eq!(
+            vec![(1, 1), (1, 2), (2, 1)],
+            anonymous
+                .timeline()
+                .iter()
+                .map(|event| (event.day, event.part))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some(1140), anonymous.delta(1));
+        assert_eq!(None, anonymous.delta(2));
+        assert_eq!(
+            Some(600),
+            leaderboard.members["1"].delta(1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_day_ranking() -> miette::Result<()> {
+        let leaderboard = Leaderboard::parse(LEADERBOARD)?;
+        let ranking = leaderboard
+            .day_ranking(2)
+            .iter()
+            .map(|rank| {
+                (rank.member.id, rank.part1, rank.part2)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![(1, 100, Some(800)), (2, 600, None)],
+            ranking
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_svg() -> miette::Result<()> {
+        let svg = Leaderboard::parse(LEADERBOARD)?.svg();
+        assert_eq!(3, svg.matches("<polyline").count());
+        assert!(svg.contains("(anonymous user #2)"));
+        assert!(svg.ends_with("</svg>\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!("0:01:40", format_elapsed(100));
+        assert_eq!("26:00:05", format_elapsed(93_605));
+    }
+}
@@ -0,0 +1,70 @@
+use clap::{Parser, Subcommand};
+use tracing_subscriber::EnvFilter;
+
+mod answers;
+mod cache;
+mod dashboard;
+mod days;
+mod inputs;
+mod leaderboard;
+mod new;
+mod run;
+mod table;
+mod users;
+mod watch;
+
+#[derive(Parser, Debug)]
+#[clap(version)]
+/// Workspace tooling for the advent of code days
+struct Args {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Browse every day in a terminal dashboard
+    Dashboard,
+    /// Manage encrypted puzzle inputs
+    Inputs {
+        #[clap(subcommand)]
+        command: inputs::Command,
+    },
+    /// Statistics for a private leaderboard from
+    /// its exported JSON
+    Leaderboard(leaderboard::LeaderboardArgs),
+    /// Create a new day from `daily-template`
+    New(new::NewArgs),
+    /// Manage answers cached by `aoc run`
+    Cache {
+        #[clap(subcommand)]
+        command: cache::Command,
+    },
+    /// Run solutions against the puzzle inputs
+    /// and check them against the recorded
+    /// answers
+    Run(run::RunArgs),
+    /// Rebuild, test and run a part whenever the
+    /// day's sources change
+    Watch(watch::WatchArgs),
+}
+
+fn main() -> miette::Result<()> {
+    // stdout is reserved for json/csv output
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    match Args::parse().command {
+        Command::Cache { command } => cache::run(command),
+        Command::Dashboard => dashboard::run(),
+        Command::Inputs { command } => inputs::run(command),
+        Command::Leaderboard(args) => {
+            leaderboard::run(args)
+        }
+        Command::New(args) => new::run(args),
+        Command::Run(args) => run::run(args),
+        Command::Watch(args) => watch::run(args),
+    }
+}
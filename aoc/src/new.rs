@@ -0,0 +1,346 @@
+use std::{fs, path::Path};
+
+use aoc_common::{input, workspace_root};
+use clap::Args;
+use miette::{miette, Context, IntoDiagnostic};
+use toml_edit::{DocumentMut, InlineTable, Item};
+
+use crate::{days, inputs, users::User};
+
+/// Files of `daily-template`, embedded so that
+/// `aoc new` doesn't need `cargo-generate`.
+const TEMPLATES: &[(&str, &str)] = &[
+    (
+        "Cargo.toml",
+        include_str!("../../daily-template/Cargo.toml"),
+    ),
+    (
+        "benches/benchmarks.rs",
+        include_str!(
+            "../../daily-template/benches/benchmarks.rs"
+        ),
+    ),
+    (
+        "benches/benchmarks-criterion.rs",
+        include_str!(
+            "../../daily-template/benches/benchmarks-criterion.rs"
+        ),
+    ),
+    (
+        "src/lib.rs",
+        include_str!("../../daily-template/src/lib.rs"),
+    ),
+    (
+        "src/custom_error.rs",
+        include_str!(
+            "../../daily-template/src/custom_error.rs"
+        ),
+    ),
+    (
+        "src/part1.rs",
+        include_str!("../../daily-template/src/part1.rs"),
+    ),
+    (
+        "src/part2.rs",
+        include_str!("../../daily-template/src/part2.rs"),
+    ),
+    (
+        "src/bin/part1.rs",
+        include_str!(
+            "../../daily-template/src/bin/part1.rs"
+        ),
+    ),
+    (
+        "src/bin/part2.rs",
+        include_str!(
+            "../../daily-template/src/bin/part2.rs"
+        ),
+    ),
+];
+
+#[derive(Args, Debug)]
+pub struct NewArgs {
+    /// day is expected to be formatted as
+    /// `day-01` to match all other commands
+    /// in the repo
+    day: String,
+    /// fetch the puzzle input using the `SESSION`
+    /// cookie, encrypting it if an input key is
+    /// configured
+    #[clap(long)]
+    fetch: bool,
+}
+
+pub fn run(args: NewArgs) -> miette::Result<()> {
+    let day = parse_day(&args.day)?;
+    let root = workspace_root();
+    let dir = root.join(&args.day);
+    if dir.exists() {
+        return Err(miette!(
+            "`{}` already exists, refusing to overwrite it",
+            dir.display()
+        ));
+    }
+
+    for (path, template) in TEMPLATES {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap())
+            .into_diagnostic()?;
+        fs::write(&path, render(template, &args.day))
+            .into_diagnostic()
+            .wrap_err_with(|| {
+                format!("writing `{}`", path.display())
+            })?;
+    }
+    add_to_workspace(&root.join("Cargo.toml"), &args.day)?;
+    register(&root, &args.day)?;
+    println!("created `{}`", dir.display());
+
+    if args.fetch {
+        fetch_input(day, &root, &args.day)?;
+    }
+    Ok(())
+}
+
+/// Parse a `day-01` style name into its day
+/// number.
+fn parse_day(name: &str) -> miette::Result<u32> {
+    name.strip_prefix("day-")
+        .filter(|number| number.len() == 2)
+        .and_then(|number| number.parse::<u32>().ok())
+        .filter(|day| (1..=25).contains(day))
+        .ok_or_else(|| {
+            miette!(
+                "day `{name}` must be formatted as `day-01`"
+            )
+        })
+}
+
+fn render(template: &str, project_name: &str) -> String {
+    template
+        .replace("{{project-name}}", project_name)
+        .replace(
+            "{{crate_name}}",
+            &project_name.replace('-', "_"),
+        )
+}
+
+/// The workspace picks up days with a `day-*`
+/// glob, only list the day explicitly if that
+/// glob has been removed.
+fn add_to_workspace(
+    manifest: &Path,
+    project_name: &str,
+) -> miette::Result<()> {
+    let contents =
+        fs::read_to_string(manifest).into_diagnostic()?;
+    let members_line = contents
+        .lines()
+        .find(|line| line.starts_with("members"))
+        .ok_or_else(|| {
+            miette!("workspace manifest has no members")
+        })?;
+    if members_line.contains("\"day-*\"")
+        || members_line
+            .contains(&format!("\"{project_name}\""))
+    {
+        return Ok(());
+    }
+    let updated = contents.replacen(
+        "members = [",
+        &format!("members = [\"{project_name}\", "),
+        1,
+    );
+    fs::write(manifest, updated).into_diagnostic()
+}
+
+/// Add the day to the `aoc` binary's dependencies
+/// and to [`days::DAYS`].
+fn register(
+    root: &Path,
+    project_name: &str,
+) -> miette::Result<()> {
+    let manifest = root.join("aoc/Cargo.toml");
+    let contents =
+        fs::read_to_string(&manifest).into_diagnostic()?;
+    fs::write(
+        &manifest,
+        add_dependency(&contents, project_name)?,
+    )
+    .into_diagnostic()?;
+
+    let registry = root.join("aoc/src/days.rs");
+    let contents =
+        fs::read_to_string(&registry).into_diagnostic()?;
+    let entry = format!(
+        "    day!(\"{project_name}\", {}),\n",
+        project_name.replace('-', "_")
+    );
+    fs::write(
+        &registry,
+        contents.replacen(
+            days::MARKER,
+            &format!("{entry}{}", days::MARKER),
+            1,
+        ),
+    )
+    .into_diagnostic()
+}
+
+/// `contents` with `project_name` added to its
+/// `[dependencies]` by path, wherever that
+/// section is in the manifest
+fn add_dependency(
+    contents: &str,
+    project_name: &str,
+) -> miette::Result<String> {
+    let mut manifest = contents
+        .parse::<DocumentMut>()
+        .into_diagnostic()?;
+    let dependencies = manifest["dependencies"]
+        .as_table_like_mut()
+        .ok_or_else(|| {
+            miette!("`aoc/Cargo.toml` has no dependencies")
+        })?;
+    let mut dependency = InlineTable::new();
+    dependency.insert(
+        "path",
+        format!("../{project_name}").into(),
+    );
+    dependencies.insert(
+        project_name,
+        Item::Value(dependency.into()),
+    );
+    Ok(manifest.to_string())
+}
+
+/// Save the input as the default user's, in
+/// `inputs/default/day-XX.txt`
+fn fetch_input(
+    day: u32,
+    root: &Path,
+    project_name: &str,
+) -> miette::Result<()> {
+    let session = std::env::var("SESSION").map_err(|_| {
+        miette!(
+            help = "copy the `session` cookie from adventofcode.com into SESSION",
+            "fetching the input needs a SESSION token"
+        )
+    })?;
+    let url = format!(
+        "https://adventofcode.com/2023/day/{day}/input"
+    );
+    println!("sending to `{}`", url);
+    let input_data = ureq::get(&url)
+        .set("Cookie", &format!("session={session}"))
+        .call()
+        .into_diagnostic()?
+        .into_string()
+        .into_diagnostic()?;
+
+    let (dir, name) =
+        User::default().input_location(root, project_name);
+    fs::create_dir_all(&dir).into_diagnostic()?;
+    let path = dir.join(name);
+    fs::write(&path, input_data).into_diagnostic()?;
+    match input::find_key()? {
+        Some(identity) => inputs::encrypt_input(
+            &path,
+            &identity.to_public(),
+            false,
+        ),
+        None => {
+            println!(
+                "no input key configured, leaving the input in plain text"
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_day() {
+        assert_eq!(13, parse_day("day-13").unwrap());
+        assert_eq!(1, parse_day("day-01").unwrap());
+        assert!(parse_day("day-1").is_err());
+        assert!(parse_day("13").is_err());
+        assert!(parse_day("day-26").is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            "use day_13::part1::process; // day-13",
+            render(
+                "use {{crate_name}}::part1::process; // {{project-name}}",
+                "day-13"
+            )
+        );
+    }
+
+    #[test]
+    fn test_add_dependency() -> miette::Result<()> {
+        let manifest = "[package]
+name = \"aoc\"
+
+[dependencies]
+day-01 = { path = \"../day-01\" }
+
+[dev-dependencies]
+rstest = \"0.19\"
+";
+        assert_eq!(
+            "[package]
+name = \"aoc\"
+
+[dependencies]
+day-01 = { path = \"../day-01\" }
+day-13 = { path = \"../day-13\" }
+
+[dev-dependencies]
+rstest = \"0.19\"
+",
+            add_dependency(manifest, "day-13")?
+        );
+        assert!(
+            add_dependency("[package]", "day-13").is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_templates_are_complete() {
+        for path in [
+            "benches/benchmarks.rs",
+            "benches/benchmarks-criterion.rs",
+        ] {
+            let manifest = TEMPLATES[0].1;
+            assert!(manifest.contains(path));
+            assert!(TEMPLATES
+                .iter()
+                .any(|(template, _)| *template == path));
+        }
+    }
+
+    /// Every line of a new day fits the
+    /// workspace's `max_width`, so it starts out
+    /// passing `cargo fmt --check`
+    #[test]
+    fn test_templates_fit_max_width() {
+        for (path, template) in TEMPLATES {
+            if !path.ends_with(".rs") {
+                continue;
+            }
+            for line in render(template, "day-13").lines() {
+                assert!(
+                    line.chars().count() <= 60,
+                    "{path}: {line}"
+                );
+            }
+        }
+    }
+}
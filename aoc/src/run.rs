@@ -0,0 +1,530 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use clap::{ArgGroup, Args, ValueEnum};
+use miette::miette;
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    answers::Answers,
+    cache::{self, Cache, Entry},
+    days::{self, Day, DAYS},
+    table,
+    users::{self, User},
+};
+
+#[derive(Args, Debug)]
+#[clap(group(
+    ArgGroup::new("target").required(true).args(["day", "all"])
+))]
+pub struct RunArgs {
+    /// day to run, formatted as `day-01`
+    day: Option<String>,
+    /// part to run, both parts run when omitted
+    #[clap(value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// run every registered day
+    #[clap(long)]
+    all: bool,
+    /// run parts in parallel, timings will
+    /// include contention between parts
+    #[clap(long)]
+    parallel: bool,
+    #[clap(long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+    /// record answers for parts that don't have a
+    /// recorded answer yet
+    #[clap(long)]
+    record: bool,
+    /// ignore cached answers and run every part,
+    /// the cache is refreshed with the new
+    /// answers
+    #[clap(long)]
+    no_cache: bool,
+    /// run a teammate's inputs from
+    /// `inputs/<user>` against their answers
+    #[clap(long, conflicts_with = "all_users")]
+    user: Option<String>,
+    /// run every user's inputs
+    #[clap(long)]
+    all_users: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// matches the recorded answer
+    Correct,
+    /// differs from the recorded answer
+    Wrong,
+    /// no answer has been recorded
+    Unknown,
+    /// the input couldn't be loaded or the
+    /// solution failed
+    Error,
+}
+
+/// A part to run against one user's input
+pub struct Job<'a> {
+    pub day: &'a Day,
+    pub part: usize,
+    pub user: &'a User,
+    pub expected: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PartResult {
+    pub day: &'static str,
+    pub part: usize,
+    pub user: String,
+    pub answer: Option<String>,
+    pub error: Option<String>,
+    pub expected: Option<String>,
+    pub status: Status,
+    /// the answer came from the cache rather than
+    /// running the solution
+    pub cached: bool,
+    #[serde(skip)]
+    pub input_hash: Option<String>,
+    #[serde(skip)]
+    pub source_hash: Option<String>,
+    #[serde(
+        rename = "duration_ms",
+        serialize_with = "as_millis"
+    )]
+    pub duration: Duration,
+}
+
+pub fn run(args: RunArgs) -> miette::Result<()> {
+    let days: Vec<&Day> = match &args.day {
+        Some(name) => {
+            vec![days::find(name).ok_or_else(|| {
+                miette!("`{name}` is not a registered day")
+            })?]
+        }
+        None => DAYS.iter().collect(),
+    };
+    let parts: Vec<usize> = match args.part {
+        Some(part) => vec![part as usize],
+        None => vec![1, 2],
+    };
+    let users = if args.all_users {
+        users::all()?
+    } else if let Some(name) = &args.user {
+        vec![users::find(name)?]
+    } else {
+        vec![User::default()]
+    };
+    let mut answers = users
+        .iter()
+        .map(Answers::load_for)
+        .collect::<miette::Result<Vec<_>>>(
+    )?;
+    let jobs = jobs(&days, &parts, &users, &answers);
+
+    let mut cache = Cache::load()?;
+    let results = run_parts(
+        &jobs,
+        (!args.no_cache).then_some(&cache),
+        args.parallel,
+    );
+    for result in &results {
+        cache_result(&mut cache, result);
+    }
+    cache.save()?;
+
+    match args.format {
+        Format::Table => {
+            print_table(&results, users.len() > 1)
+        }
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&results).unwrap()
+        ),
+        Format::Csv => print_csv(&results),
+    }
+
+    if args.record {
+        for (user, answers) in
+            users.iter().zip(&mut answers)
+        {
+            for result in results
+                .iter()
+                .filter(|result| result.user == user.name)
+            {
+                if let (Status::Unknown, Some(answer)) =
+                    (result.status, &result.answer)
+                {
+                    answers.set(
+                        result.day,
+                        result.part,
+                        answer.clone(),
+                    );
+                }
+            }
+            answers.save()?;
+        }
+    }
+
+    let failures = results
+        .iter()
+        .filter(|result| {
+            matches!(
+                result.status,
+                Status::Wrong | Status::Error
+            )
+        })
+        .count();
+    if failures > 0 {
+        return Err(miette!("{failures} part(s) failed"));
+    }
+    Ok(())
+}
+
+/// Every day and part for every user, expecting
+/// each user's recorded answers
+fn jobs<'a>(
+    days: &[&'a Day],
+    parts: &[usize],
+    users: &'a [User],
+    answers: &[Answers],
+) -> Vec<Job<'a>> {
+    users
+        .iter()
+        .zip(answers)
+        .flat_map(|(user, answers)| {
+            days.iter().flat_map(move |&day| {
+                parts.iter().map(move |&part| Job {
+                    day,
+                    part,
+                    user,
+                    expected: answers
+                        .get(day.name, part)
+                        .map(str::to_string),
+                })
+            })
+        })
+        .collect()
+}
+
+pub fn run_parts(
+    jobs: &[Job],
+    cache: Option<&Cache>,
+    parallel: bool,
+) -> Vec<PartResult> {
+    // solutions that are still `todo!()` panic, the
+    // panic is reported as an error instead
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let run_job = |job| run_part(job, cache);
+    let results = if parallel {
+        jobs.par_iter().map(run_job).collect()
+    } else {
+        jobs.iter().map(run_job).collect()
+    };
+    panic::set_hook(hook);
+    results
+}
+
+pub fn run_part(
+    job: &Job,
+    cache: Option<&Cache>,
+) -> PartResult {
+    let Job { day, part, .. } = *job;
+    let expected = job.expected.as_deref();
+    let mut result = PartResult {
+        day: day.name,
+        part,
+        user: job.user.name.clone(),
+        answer: None,
+        error: None,
+        expected: job.expected.clone(),
+        status: Status::Error,
+        cached: false,
+        input_hash: None,
+        source_hash: None,
+        duration: Duration::ZERO,
+    };
+    let input = match job.user.input(day.name) {
+        Ok(input) => input,
+        Err(error) => {
+            result.error = Some(error.to_string());
+            return result;
+        }
+    };
+
+    let input_hash = cache::hash_bytes(input.as_bytes());
+    let source_hash = cache::source_hash(day.name).ok();
+    if let Some(entry) = cache
+        .zip(source_hash.as_ref())
+        .and_then(|(cache, source_hash)| {
+            cache.get(
+                day.name,
+                part,
+                &input_hash,
+                source_hash,
+            )
+        })
+    {
+        result.cached = true;
+        result.duration = entry.duration();
+        result.status = check(expected, &entry.answer);
+        result.answer = Some(entry.answer.clone());
+        return result;
+    }
+    result.input_hash = Some(input_hash);
+    result.source_hash = source_hash;
+
+    let solution = day.parts[part - 1];
+    let start = Instant::now();
+    let outcome =
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            solution(&input)
+        }));
+    result.duration = start.elapsed();
+
+    match outcome {
+        Ok(Ok(answer)) => {
+            result.status = check(expected, &answer);
+            result.answer = Some(answer);
+        }
+        Ok(Err(report)) => {
+            result.error = Some(report.to_string());
+        }
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| {
+                    payload
+                        .downcast_ref::<String>()
+                        .cloned()
+                })
+                .unwrap_or_else(|| {
+                    "unknown panic".to_string()
+                });
+            result.error =
+                Some(format!("panicked: {message}"));
+        }
+    }
+    result
+}
+
+/// Store a freshly computed answer, results that
+/// came from the cache or failed are skipped
+pub fn cache_result(
+    cache: &mut Cache,
+    result: &PartResult,
+) {
+    if let (
+        false,
+        Some(answer),
+        Some(input_hash),
+        Some(source_hash),
+    ) = (
+        result.cached,
+        &result.answer,
+        &result.input_hash,
+        &result.source_hash,
+    ) {
+        cache.insert(Entry {
+            day: result.day.to_string(),
+            part: result.part,
+            input_hash: input_hash.clone(),
+            source_hash: source_hash.clone(),
+            build: cache::build(),
+            answer: answer.clone(),
+            duration_ms: result.duration.as_secs_f64()
+                * 1000.0,
+        });
+    }
+}
+
+fn check(expected: Option<&str>, answer: &str) -> Status {
+    match expected {
+        Some(expected) if expected == answer => {
+            Status::Correct
+        }
+        Some(_) => Status::Wrong,
+        None => Status::Unknown,
+    }
+}
+
+fn as_millis<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer
+        .serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+fn status_label(result: &PartResult) -> String {
+    match result.status {
+        Status::Correct => "correct".to_string(),
+        Status::Wrong => "WRONG".to_string(),
+        Status::Unknown => "unknown".to_string(),
+        Status::Error => format!(
+            "error: {}",
+            result.error.as_deref().unwrap_or_default()
+        ),
+    }
+}
+
+/// The user column is only shown when more than
+/// one user's inputs were run
+fn print_table(results: &[PartResult], show_user: bool) {
+    let rows = results
+        .iter()
+        .map(|result| {
+            let user = show_user
+                .then(|| result.user.clone())
+                .into_iter();
+            user.chain([
+                result.day.to_string(),
+                format!("part{}", result.part),
+                result.answer.clone().unwrap_or_default(),
+                result.expected.clone().unwrap_or_default(),
+                if result.cached {
+                    format!(
+                        "{:.2?} (cached)",
+                        result.duration
+                    )
+                } else {
+                    format!("{:.2?}", result.duration)
+                },
+                status_label(result),
+            ])
+            .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let header = [
+        "user", "day", "part", "answer", "expected",
+        "time", "status",
+    ];
+    table::print(
+        &header[usize::from(!show_user)..],
+        &rows,
+    );
+}
+
+fn print_csv(results: &[PartResult]) {
+    println!(
+        "user,day,part,answer,expected,status,cached,duration_ms,error"
+    );
+    for result in results {
+        println!(
+            "{},{},{},{},{},{},{},{},{}",
+            csv_field(Some(&result.user)),
+            result.day,
+            result.part,
+            csv_field(result.answer.as_deref()),
+            csv_field(result.expected.as_deref()),
+            serde_json::to_value(result.status)
+                .unwrap()
+                .as_str()
+                .unwrap(),
+            result.cached,
+            result.duration.as_secs_f64() * 1000.0,
+            csv_field(result.error.as_deref()),
+        );
+    }
+}
+
+fn csv_field(field: Option<&str>) -> String {
+    let field = field.unwrap_or_default();
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Day = Day {
+        name: "day-99",
+        parts: [|input| Ok(input.len().to_string()); 2],
+    };
+
+    #[test]
+    fn test_run_part_reports_missing_input() {
+        let user = User::default();
+        let job = Job {
+            day: &DAY,
+            part: 1,
+            user: &user,
+            expected: None,
+        };
+        let result = run_part(&job, None);
+        assert_eq!(Status::Error, result.status);
+        assert!(result
+            .error
+            .unwrap()
+            .contains("no input found"));
+    }
+
+    /// Every user's inputs that are available,
+    /// plaintext or decryptable, give the answers
+    /// recorded for them
+    #[test]
+    fn test_recorded_answers() -> miette::Result<()> {
+        let users = users::all()?;
+        let answers = users
+            .iter()
+            .map(Answers::load_for)
+            .collect::<miette::Result<Vec<_>>>(
+        )?;
+        let days = DAYS.iter().collect::<Vec<_>>();
+        let jobs = jobs(&days, &[1, 2], &users, &answers)
+            .into_iter()
+            .filter(|job| {
+                job.expected.is_some()
+                    && job.user.input(job.day.name).is_ok()
+            })
+            .collect::<Vec<_>>();
+        // `run_parts` would swap the process-wide
+        // panic hook under the other tests
+        let failures = jobs
+            .par_iter()
+            .map(|job| run_part(job, None))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .filter(|result| {
+                result.status != Status::Correct
+            })
+            .map(|result| {
+                format!(
+                    "{} {} part{}: {}",
+                    result.user,
+                    result.day,
+                    result.part,
+                    status_label(&result)
+                )
+            })
+            .collect::<Vec<_>>();
+        assert!(failures.is_empty(), "{failures:#?}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!("142", csv_field(Some("142")));
+        assert_eq!("", csv_field(None));
+        assert_eq!(
+            "\"a, \"\"b\"\"\"",
+            csv_field(Some("a, \"b\""))
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! Plain text tables with columns padded to their
+//! widest cell.
+
+pub fn print<R: AsRef<[String]>>(
+    header: &[&str],
+    rows: &[R],
+) {
+    print!("{}", render(header, rows));
+}
+
+/// Every row is expected to have as many cells as
+/// the header
+pub fn render<R: AsRef<[String]>>(
+    header: &[&str],
+    rows: &[R],
+) -> String {
+    let header = header
+        .iter()
+        .map(|cell| cell.to_string())
+        .collect::<Vec<String>>();
+    let rows = std::iter::once(header.as_slice())
+        .chain(rows.iter().map(AsRef::as_ref))
+        .collect::<Vec<&[String]>>();
+    let widths = (0..header.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<usize>>();
+    rows.iter()
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| {
+                    format!("{cell:width$}")
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            "day     time
+day-01  92.97µs
+day-12  1.2s\n",
+            render(
+                &["day", "time"],
+                &[
+                    ["day-01".into(), "92.97µs".into()],
+                    ["day-12".into(), "1.2s".into()],
+                ]
+            )
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Puzzle inputs from several accounts.
+//!
+//! Every user's inputs live in
+//! `inputs/<user>/day-01.txt`, with their answers
+//! in `inputs/<user>/answers.toml`. The
+//! [`DEFAULT_USER`]'s are the ones the day
+//! binaries, benches and tests use.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub use aoc_common::input::{DEFAULT_USER, INPUTS_DIR};
+use aoc_common::{
+    input::{self, InputError},
+    workspace_root,
+};
+use miette::{miette, IntoDiagnostic};
+
+use crate::answers::ANSWERS_FILE;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct User {
+    pub name: String,
+}
+
+impl Default for User {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_USER.to_string(),
+        }
+    }
+}
+
+impl User {
+    /// `inputs/<user>`
+    pub fn dir(&self, root: &Path) -> PathBuf {
+        root.join(INPUTS_DIR).join(&self.name)
+    }
+
+    /// Directory and file name of a day's input,
+    /// shared by both parts
+    pub fn input_location(
+        &self,
+        root: &Path,
+        day: &str,
+    ) -> (PathBuf, String) {
+        (self.dir(root), format!("{day}.txt"))
+    }
+
+    pub fn input(
+        &self,
+        day: &str,
+    ) -> Result<String, InputError> {
+        let (dir, name) =
+            self.input_location(&workspace_root(), day);
+        input::load(dir, &name)
+    }
+
+    pub fn answers_path(&self) -> PathBuf {
+        self.dir(&workspace_root()).join(ANSWERS_FILE)
+    }
+}
+
+/// The default user followed by every user with a
+/// directory in `inputs`
+pub fn all() -> miette::Result<Vec<User>> {
+    list(&workspace_root())
+}
+
+pub fn find(name: &str) -> miette::Result<User> {
+    all()?
+        .into_iter()
+        .find(|user| user.name == name)
+        .ok_or_else(|| {
+            miette!(
+                help = format!("add their inputs to `{INPUTS_DIR}/{name}/day-01.txt`"),
+                "`{name}` has no inputs"
+            )
+        })
+}
+
+fn list(root: &Path) -> miette::Result<Vec<User>> {
+    let dir = root.join(INPUTS_DIR);
+    let mut users = if dir.exists() {
+        fs::read_dir(dir)
+            .into_diagnostic()?
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                if !entry.file_type().ok()?.is_dir() {
+                    return None;
+                }
+                let name =
+                    entry.file_name().into_string().ok()?;
+                (name != DEFAULT_USER)
+                    .then_some(User { name })
+            })
+            .collect::<Vec<User>>()
+    } else {
+        vec![]
+    };
+    users.sort_by(|a, b| a.name.cmp(&b.name));
+    users.insert(0, User::default());
+    Ok(users)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list() -> miette::Result<()> {
+        let root = std::env::temp_dir().join(format!(
+            "aoc-users-{}",
+            std::process::id()
+        ));
+        assert_eq!(vec![User::default()], list(&root)?);
+
+        for dir in
+            ["inputs/zoe", "inputs/default", "inputs/ann"]
+        {
+            fs::create_dir_all(root.join(dir))
+                .into_diagnostic()?;
+        }
+        fs::write(root.join("inputs/notes.txt"), "")
+            .into_diagnostic()?;
+        assert_eq!(
+            vec!["default", "ann", "zoe"],
+            list(&root)?
+                .iter()
+                .map(|user| user.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        fs::remove_dir_all(root).into_diagnostic()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_location() {
+        let root = Path::new("/aoc");
+        assert_eq!(
+            (
+                PathBuf::from("/aoc/inputs/default"),
+                "day-01.txt".to_string()
+            ),
+            User::default().input_location(root, "day-01")
+        );
+        let ann = User {
+            name: "ann".to_string(),
+        };
+        assert_eq!(
+            (
+                PathBuf::from("/aoc/inputs/ann"),
+                "day-01.txt".to_string()
+            ),
+            ann.input_location(root, "day-01")
+        );
+    }
+}
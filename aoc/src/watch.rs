@@ -0,0 +1,368 @@
+//! Rebuild a day whenever its sources or inputs
+//! change, then re-run the part's tests and the
+//! solution and print a one line summary.
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+    sync::mpsc::{self, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use aoc_common::workspace_root;
+use clap::Args;
+use miette::{miette, IntoDiagnostic};
+use notify::{
+    EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
+
+use crate::{answers::Answers, days, users::User};
+
+/// Saves closer together than this are handled as
+/// a single change
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    /// day to watch, formatted as `day-01`
+    day: String,
+    /// part to test and run, formatted as `part1`
+    #[clap(value_parser = parse_part)]
+    part: usize,
+}
+
+pub fn run(args: WatchArgs) -> miette::Result<()> {
+    if days::find(&args.day).is_none() {
+        return Err(miette!(
+            "`{}` is not a registered day",
+            args.day
+        ));
+    }
+    let root = workspace_root();
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = RecommendedWatcher::new(
+        tx,
+        notify::Config::default(),
+    )
+    .into_diagnostic()?;
+    for dir in
+        [root.join(&args.day), root.join("aoc-common")]
+    {
+        watcher
+            .watch(&dir, RecursiveMode::Recursive)
+            .into_diagnostic()?;
+    }
+    let (inputs, _) =
+        User::default().input_location(&root, &args.day);
+    if inputs.exists() {
+        watcher
+            .watch(&inputs, RecursiveMode::NonRecursive)
+            .into_diagnostic()?;
+    }
+
+    eprintln!(
+        "watching {} part{}",
+        args.day, args.part
+    );
+    check(&args.day, args.part);
+    loop {
+        let event = rx.recv().into_diagnostic()?;
+        if !is_relevant(&event.into_diagnostic()?) {
+            continue;
+        }
+        // wait until the saves settle down before
+        // rebuilding
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Ok(())
+                }
+            }
+        }
+        check(&args.day, args.part);
+    }
+}
+
+fn parse_part(part: &str) -> Result<usize, String> {
+    match part {
+        "part1" => Ok(1),
+        "part2" => Ok(2),
+        _ => Err(format!(
+            "part `{part}` must be `part1` or `part2`"
+        )),
+    }
+}
+
+/// Build output and editor temp files don't
+/// trigger a rebuild
+fn is_relevant(event: &notify::Event) -> bool {
+    !matches!(event.kind, EventKind::Access(_))
+        && event.paths.iter().any(|path| {
+            !path
+                .components()
+                .any(|part| part.as_os_str() == "target")
+                && matches!(
+                    path.extension()
+                        .and_then(|ext| ext.to_str()),
+                    Some("rs" | "toml" | "txt" | "age")
+                )
+        })
+}
+
+/// Build, test and run the part, printing a
+/// status line such as
+///
+/// ```text
+/// day-01 part1 | tests 4 passed | 54667 (correct) | 312.40µs
+/// ```
+fn check(day: &str, part: usize) {
+    let mut status = vec![format!("{day} part{part}")];
+    match build(day, part) {
+        Ok(executable) => {
+            status.push(test(day, part));
+            status.extend(solve(day, part, &executable));
+        }
+        Err(error) => status.push(error),
+    }
+    println!("{}", status.join(" | "));
+}
+
+/// Use the cargo that launched us when run
+/// through `cargo run`, so the same toolchain
+/// builds the day
+fn cargo(args: &[&str]) -> std::io::Result<Output> {
+    Command::new(
+        std::env::var_os("CARGO")
+            .unwrap_or_else(|| "cargo".into()),
+    )
+    .args(args)
+    .current_dir(workspace_root())
+    .stdin(Stdio::null())
+    .output()
+}
+
+/// Build the part's binary in release mode and
+/// return its path
+pub fn build(
+    day: &str,
+    part: usize,
+) -> Result<PathBuf, String> {
+    let bin = format!("part{part}");
+    let output = cargo(&[
+        "build",
+        "--release",
+        "-p",
+        day,
+        "--bin",
+        &bin,
+        "--message-format=json-render-diagnostics",
+    ])
+    .map_err(|error| format!("cargo failed: {error}"))?;
+    if !output.status.success() {
+        eprint!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return Err("build FAILED".to_string());
+    }
+    find_executable(
+        &String::from_utf8_lossy(&output.stdout),
+        &bin,
+    )
+    .ok_or_else(|| "build produced no binary".to_string())
+}
+
+/// Find the binary among cargo's json messages
+fn find_executable(
+    messages: &str,
+    bin: &str,
+) -> Option<PathBuf> {
+    messages
+        .lines()
+        .filter_map(|line| {
+            serde_json::from_str::<serde_json::Value>(line)
+                .ok()
+        })
+        .filter(|message| {
+            message["reason"] == "compiler-artifact"
+                && message["target"]["name"] == bin
+        })
+        .find_map(|message| {
+            message["executable"]
+                .as_str()
+                .map(PathBuf::from)
+        })
+}
+
+fn test(day: &str, part: usize) -> String {
+    match run_tests(day, part) {
+        Ok(run) if run.success => {
+            format!("tests {} passed", run.passed)
+        }
+        Ok(run) => {
+            eprint!("{}", run.output);
+            "tests FAILED".to_string()
+        }
+        Err(error) => format!("cargo failed: {error}"),
+    }
+}
+
+pub struct TestRun {
+    pub success: bool,
+    pub passed: usize,
+    /// libtest's stdout followed by cargo's
+    /// stderr
+    pub output: String,
+}
+
+/// Run the example tests in the part's module
+pub fn run_tests(
+    day: &str,
+    part: usize,
+) -> std::io::Result<TestRun> {
+    let filter = format!("part{part}::");
+    let output = cargo(&[
+        "test", "-p", day, "--lib", "--", &filter,
+    ])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(TestRun {
+        success: output.status.success(),
+        passed: count_passed(&stdout),
+        output: format!(
+            "{stdout}{}",
+            String::from_utf8_lossy(&output.stderr)
+        ),
+    })
+}
+
+/// Sum the passed counts of every `test result`
+/// line in libtest's output
+fn count_passed(stdout: &str) -> usize {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("test result: ")?
+                .split_once(". ")?
+                .1
+                .split_once(" passed")?
+                .0
+                .parse::<usize>()
+                .ok()
+        })
+        .sum()
+}
+
+fn solve(
+    day: &str,
+    part: usize,
+    executable: &Path,
+) -> Vec<String> {
+    let start = Instant::now();
+    let output = Command::new(executable)
+        .current_dir(workspace_root().join(day))
+        .stdin(Stdio::null())
+        .output();
+    let elapsed = start.elapsed();
+    match output {
+        Ok(output) if output.status.success() => {
+            let answer =
+                String::from_utf8_lossy(&output.stdout)
+                    .trim()
+                    .to_string();
+            let expected =
+                Answers::load().ok().and_then(|answers| {
+                    answers
+                        .get(day, part)
+                        .map(str::to_string)
+                });
+            let verdict = match expected {
+                Some(expected) if expected == answer => {
+                    "correct"
+                }
+                Some(_) => "WRONG",
+                None => "unknown",
+            };
+            vec![
+                format!("{answer} ({verdict})"),
+                format!("{elapsed:.2?}"),
+            ]
+        }
+        Ok(output) => {
+            eprint!(
+                "{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            vec!["run FAILED".to_string()]
+        }
+        Err(error) => vec![format!("run failed: {error}")],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_part() {
+        assert_eq!(Ok(1), parse_part("part1"));
+        assert_eq!(Ok(2), parse_part("part2"));
+        assert!(parse_part("3").is_err());
+    }
+
+    #[test]
+    fn test_count_passed() {
+        assert_eq!(
+            5,
+            count_passed(
+                "running 4 tests
+test result: ok. 4 passed; 0 failed; 0 ignored; 0 measured; 2 filtered out; finished in 0.00s
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s"
+            )
+        );
+    }
+
+    #[test]
+    fn test_find_executable() {
+        let messages = r#"{"reason":"compiler-artifact","target":{"name":"day_01"},"executable":null}
+{"reason":"compiler-artifact","target":{"name":"part1"},"executable":"/target/release/part1"}
+{"reason":"build-finished","success":true}"#;
+        assert_eq!(
+            Some(PathBuf::from("/target/release/part1")),
+            find_executable(messages, "part1")
+        );
+        assert_eq!(
+            None,
+            find_executable(messages, "part2")
+        );
+    }
+
+    #[test]
+    fn test_is_relevant() {
+        let event = |kind, path: &str| {
+            notify::Event::new(kind).add_path(path.into())
+        };
+        let modify = EventKind::Modify(
+            notify::event::ModifyKind::Any,
+        );
+        assert!(is_relevant(&event(
+            modify,
+            "/aoc/day-01/src/part1.rs"
+        )));
+        assert!(!is_relevant(&event(
+            modify,
+            "/aoc/day-01/target/release/part1"
+        )));
+        assert!(!is_relevant(&event(
+            modify,
+            "/aoc/day-01/src/.part1.rs.swp"
+        )));
+        assert!(!is_relevant(&event(
+            EventKind::Access(
+                notify::event::AccessKind::Any
+            ),
+            "/aoc/day-01/src/part1.rs"
+        )));
+    }
+}
@@ -1,4 +1,5 @@
-use day_01::*;
+use aoc_common::variants::Variant;
+use day_01::{custom_error::AocError, *};
 
 fn main() {
     // Run registered benchmarks.
@@ -6,17 +7,24 @@ fn main() {
 }
 
 #[divan::bench]
-fn part1() {
-    part1::process(divan::black_box(include_str!(
-        "../input1.txt",
-    )))
-    .unwrap();
+fn part1(bencher: divan::Bencher) {
+    let input =
+        aoc_common::input::load_day(env!("CARGO_PKG_NAME"))
+            .unwrap();
+    bencher.bench(|| {
+        part1::process(divan::black_box(&input)).unwrap()
+    });
 }
 
-#[divan::bench]
-fn part2() {
-    part2::process(divan::black_box(include_str!(
-        "../input2.txt",
-    )))
-    .unwrap();
-}
\ No newline at end of file
+#[divan::bench(args = part2::VARIANTS)]
+fn part2(
+    bencher: divan::Bencher,
+    variant: &Variant<u32, AocError>,
+) {
+    let input =
+        aoc_common::input::load_day(env!("CARGO_PKG_NAME"))
+            .unwrap();
+    bencher.bench(|| {
+        (variant.process)(divan::black_box(&input)).unwrap()
+    });
+}
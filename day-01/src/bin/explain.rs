@@ -0,0 +1,36 @@
+//! Print the tokens behind every line's
+//! calibration value, as a table or with
+//! `--json`. Takes the same `--param
+//! vocabulary=...` as part 2.
+use aoc_common::params::{overrides, Params as _};
+use day_01::{
+    explain::{explain, to_json, to_table},
+    part2::Params,
+    vocabulary::Vocabulary,
+};
+use miette::IntoDiagnostic;
+
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let (json, args): (Vec<String>, Vec<String>) =
+        std::env::args()
+            .skip(1)
+            .partition(|arg| arg == "--json");
+    let params = Params::with(overrides(args)?)?;
+    let scanner =
+        Vocabulary::find(&params.vocabulary)?.scanner();
+    let file = aoc_common::input::load_day(env!(
+        "CARGO_PKG_NAME"
+    ))?;
+    let explanations = explain(&scanner, &file);
+    if json.is_empty() {
+        print!("{}", to_table(&explanations));
+    } else {
+        println!(
+            "{}",
+            to_json(&explanations).into_diagnostic()?
+        );
+    }
+    Ok(())
+}
@@ -10,4 +10,12 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::parse_error))]
     ParseError(#[from] std::num::ParseIntError),
+
+    #[error("no digit or digit word in `{0}`")]
+    #[diagnostic(code(aoc::no_digit))]
+    NoDigit(String),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Vocabulary(#[from] crate::vocabulary::VocabularyError),
 }
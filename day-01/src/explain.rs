@@ -0,0 +1,231 @@
+//! Which tokens each line's calibration value
+//! came from, found with the same [`Scanner`] as
+//! [`process`](crate::part2::process), for
+//! tracking down a wrong answer.
+use std::fmt::Write;
+
+use aoc_common::lines::input_lines;
+use serde::Serialize;
+
+use crate::scanner::{Scanner, Token};
+
+/// A token with the text it matched
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Match<'a> {
+    /// Byte offset of the first byte
+    pub start: usize,
+    /// Byte offset just past the last byte
+    pub end: usize,
+    pub text: &'a str,
+    pub value: u32,
+}
+
+impl<'a> Match<'a> {
+    fn new(line: &'a str, token: Token) -> Self {
+        let end = token.start + token.len;
+        Self {
+            start: token.start,
+            end,
+            text: &line[token.start..end],
+            value: token.value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Explanation<'a> {
+    /// Counting from 1
+    pub line: usize,
+    pub text: &'a str,
+    /// Missing when the line has no digit, which
+    /// `process` reports as an error
+    pub first: Option<Match<'a>>,
+    pub last: Option<Match<'a>>,
+    pub value: Option<u32>,
+}
+
+/// Explain every line of `input`
+pub fn explain<'a>(
+    scanner: &Scanner,
+    input: &'a str,
+) -> Vec<Explanation<'a>> {
+    input_lines(input)
+        .enumerate()
+        .map(|(i, text)| {
+            let tokens =
+                scanner.first_and_last(text.as_bytes());
+            Explanation {
+                line: i + 1,
+                text,
+                first: tokens.map(|(first, _)| {
+                    Match::new(text, first)
+                }),
+                last: tokens.map(|(_, last)| {
+                    Match::new(text, last)
+                }),
+                value: tokens.map(|(first, last)| {
+                    first.value * 10 + last.value
+                }),
+            }
+        })
+        .collect()
+}
+
+/// The sum `process` would give, or `None` if a
+/// line has no digit
+pub fn total(explanations: &[Explanation]) -> Option<u32> {
+    explanations.iter().map(|line| line.value).sum()
+}
+
+fn describe(token: &Option<Match>) -> String {
+    token.as_ref().map_or("-".to_string(), |token| {
+        format!(
+            "{} @{}..{}",
+            token.text, token.start, token.end
+        )
+    })
+}
+
+/// One row per line, then the total
+pub fn to_table(explanations: &[Explanation]) -> String {
+    let rows = explanations
+        .iter()
+        .map(|line| {
+            [
+                line.line.to_string(),
+                describe(&line.first),
+                describe(&line.last),
+                line.value
+                    .map_or("-".to_string(), |value| {
+                        value.to_string()
+                    }),
+                line.text.to_string(),
+            ]
+        })
+        .collect::<Vec<[String; 5]>>();
+    let header = ["line", "first", "last", "value", "text"];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    let header = header.map(str::to_string);
+    for row in std::iter::once(&header).chain(&rows) {
+        // numbers on the right, text on the left
+        let _ = writeln!(
+            table,
+            "{:>w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            row[4],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+        );
+    }
+    match total(explanations) {
+        Some(total) => {
+            let _ = writeln!(table, "total {total}");
+        }
+        None => {
+            let _ = writeln!(
+                table,
+                "no total, some lines have no digit"
+            );
+        }
+    }
+    table
+}
+
+pub fn to_json(
+    explanations: &[Explanation],
+) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(explanations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part2, vocabulary::Vocabulary};
+
+    const INPUT: &str = "two1nine
+eightwothree
+xtwone3four
+nothing";
+
+    #[test]
+    fn test_explain() {
+        let scanner = Vocabulary::english().scanner();
+        let explanations = explain(&scanner, INPUT);
+        assert_eq!(4, explanations.len());
+        let xtwone = &explanations[2];
+        assert_eq!(
+            Some(Match {
+                start: 1,
+                end: 4,
+                text: "two",
+                value: 2
+            }),
+            xtwone.first
+        );
+        assert_eq!(
+            Some(Match {
+                start: 7,
+                end: 11,
+                text: "four",
+                value: 4
+            }),
+            xtwone.last
+        );
+        assert_eq!(Some(24), xtwone.value);
+        assert_eq!(None, explanations[3].value);
+        assert_eq!(None, total(&explanations));
+
+        let explanations = &explanations[..3];
+        let input = INPUT.rsplit_once('\n').unwrap().0;
+        assert_eq!(
+            part2::process(input).ok(),
+            total(explanations)
+        );
+    }
+
+    #[test]
+    fn test_to_table() {
+        let scanner = Vocabulary::english().scanner();
+        assert_eq!(
+            "line  first        last          value  text
+   1  two @0..3    nine @4..8       29  two1nine
+   2  eight @0..5  three @7..12     83  eightwothree
+   3  two @1..4    four @7..11      24  xtwone3four
+   4  -            -                 -  nothing
+no total, some lines have no digit
+",
+            to_table(&explain(&scanner, INPUT))
+        );
+    }
+
+    #[test]
+    fn test_to_json() -> serde_json::Result<()> {
+        let scanner = Vocabulary::english().scanner();
+        let json = to_json(&explain(&scanner, "a7"))?;
+        let value: serde_json::Value =
+            serde_json::from_str(&json)?;
+        assert_eq!(
+            serde_json::json!([{
+                "line": 1,
+                "text": "a7",
+                "first": {"start": 1, "end": 2, "text": "7", "value": 7},
+                "last": {"start": 1, "end": 2, "text": "7", "value": 7},
+                "value": 77
+            }]),
+            value
+        );
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
 pub mod custom_error;
+pub mod explain;
 
 pub mod part1;
 pub mod part2;
+pub mod scanner;
+pub mod vocabulary;
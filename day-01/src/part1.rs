@@ -1,4 +1,6 @@
 use crate::custom_error::AocError;
+use aoc_common::lines::{input_lines, try_for_each_line};
+use std::io::BufRead;
 
 #[tracing::instrument]
 pub fn process(
@@ -8,17 +10,31 @@ pub fn process(
     Ok(part_numbers.into_iter().sum::<u32>())
 }
 
+/// Same as [`process`], reading one line at a
+/// time
+pub fn process_reader(
+    reader: impl BufRead,
+) -> miette::Result<u32, AocError> {
+    let mut sum = 0;
+    try_for_each_line(reader, |line| {
+        sum += parse_line(line)?;
+        Ok::<_, AocError>(())
+    })?;
+    Ok(sum)
+}
+
 fn parse_input(
     input: &str,
 ) -> miette::Result<Vec<u32>, AocError> {
-    input.lines().map(parse_line).collect()
+    input_lines(input).map(parse_line).collect()
 }
 
 fn parse_line(line: &str) -> miette::Result<u32, AocError> {
     let mut digits =
         line.chars().filter_map(|c| c.to_digit(10));
     let first = digits.next().expect("At least one digit");
-    let part_value = if let Some(last) = digits.last() {
+    let part_value = if let Some(last) = digits.next_back()
+    {
         format!("{first}{last}")
     } else {
         format!("{first}{first}")
@@ -30,6 +46,7 @@ fn parse_line(line: &str) -> miette::Result<u32, AocError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::lines::assert_all_formats;
 
     const EXAMPLE: &str = "1abc2
 pqr3stu8vwx
@@ -40,4 +57,23 @@ treb7uchet";
     fn test_day1() {
         assert_eq!(process(EXAMPLE).unwrap(), 142);
     }
+
+    #[test]
+    fn test_input_formats() {
+        assert_all_formats(process, EXAMPLE, 142);
+        assert_all_formats(
+            |input| process_reader(input.as_bytes()),
+            EXAMPLE,
+            142,
+        );
+    }
+
+    #[test]
+    fn test_process_reader_agrees() -> miette::Result<()> {
+        assert_eq!(
+            process(EXAMPLE)?,
+            process_reader(EXAMPLE.as_bytes())?
+        );
+        Ok(())
+    }
 }
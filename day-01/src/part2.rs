@@ -1,30 +1,156 @@
-use crate::custom_error::AocError;
+use crate::{
+    custom_error::AocError, scanner::Scanner,
+    vocabulary::Vocabulary,
+};
+use aoc_common::{
+    lines::{input_lines, try_for_each_line},
+    variants::Variant,
+};
 use nom::InputIter;
 use regex::Regex;
-use std::str;
+use std::{io::BufRead, str, sync::LazyLock};
+
+pub const VARIANTS: &[Variant<u32, AocError>] = &[
+    Variant {
+        name: "automaton",
+        process,
+    },
+    Variant {
+        name: "bytes",
+        process: process_bytes,
+    },
+    Variant {
+        name: "regex",
+        process: process_regex,
+    },
+];
+
+pub(crate) const WORDS: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six",
+    "seven", "eight", "nine",
+];
+
+aoc_common::params! {
+    pub struct Params {
+        /// a built-in vocabulary, a `.toml` or
+        /// `.json` file, or several joined with `+`
+        vocabulary: String = "english".to_string(),
+    }
+}
+
+/// Built on first use and shared by every call
+static ENGLISH: LazyLock<Scanner> =
+    LazyLock::new(|| Vocabulary::english().scanner());
 
 #[tracing::instrument]
 pub fn process(
     input: &str,
 ) -> miette::Result<u32, AocError> {
-    let part_numbers = parse_input(input)?;
-    Ok(part_numbers.into_iter().sum::<u32>())
+    input_lines(input)
+        .map(|line| parse_line(&ENGLISH, line))
+        .sum()
 }
 
-fn parse_input(
+/// Same as [`process`], spelling digits with the
+/// words of `params.vocabulary`
+pub fn process_with(
     input: &str,
-) -> miette::Result<Vec<u32>, AocError> {
-    input.lines().map(parse_line).collect()
+    params: &Params,
+) -> miette::Result<u32, AocError> {
+    let scanner =
+        Vocabulary::find(&params.vocabulary)?.scanner();
+    input_lines(input)
+        .map(|line| parse_line(&scanner, line))
+        .sum()
 }
 
-fn parse_line(line: &str) -> miette::Result<u32, AocError> {
+/// Scanning from each end of every line, trying
+/// each word at each position
+pub fn process_bytes(
+    input: &str,
+) -> miette::Result<u32, AocError> {
+    input_lines(input).map(parse_line_bytes).sum()
+}
+
+/// The original solution, searching each line
+/// forwards and backwards with a regex
+pub fn process_regex(
+    input: &str,
+) -> miette::Result<u32, AocError> {
+    let part_numbers = input_lines(input)
+        .map(parse_line_regex)
+        .collect::<Result<Vec<u32>, AocError>>()?;
+    Ok(part_numbers.into_iter().sum::<u32>())
+}
+
+/// Same as [`process`], reading one line at a
+/// time
+pub fn process_reader(
+    reader: impl BufRead,
+) -> miette::Result<u32, AocError> {
+    let mut sum = 0;
+    try_for_each_line(reader, |line| {
+        sum += parse_line(&ENGLISH, line)?;
+        Ok::<_, AocError>(())
+    })?;
+    Ok(sum)
+}
+
+/// The first and last digit or digit word found
+/// by a single pass of `scanner` over the line
+fn parse_line(
+    scanner: &Scanner,
+    line: &str,
+) -> miette::Result<u32, AocError> {
+    let (first, last) = scanner
+        .first_and_last(line.as_bytes())
+        .ok_or_else(|| {
+            AocError::NoDigit(line.to_string())
+        })?;
+    Ok(first.value * 10 + last.value)
+}
+
+/// Scan from each end of the line for the first
+/// digit or digit word, so overlapping words such
+/// as "twone" count from both sides
+fn parse_line_bytes(
+    line: &str,
+) -> miette::Result<u32, AocError> {
+    let bytes = line.as_bytes();
+    let no_digit = || AocError::NoDigit(line.to_string());
+    let first = (0..bytes.len())
+        .find_map(|i| digit_at(bytes, i))
+        .ok_or_else(no_digit)?;
+    let last = (0..bytes.len())
+        .rev()
+        .find_map(|i| digit_at(bytes, i))
+        .ok_or_else(no_digit)?;
+    Ok(first * 10 + last)
+}
+
+/// The digit spelt out or written at `i`
+fn digit_at(line: &[u8], i: usize) -> Option<u32> {
+    let rest = &line[i..];
+    if rest[0].is_ascii_digit() {
+        return Some(u32::from(rest[0] - b'0'));
+    }
+    (0..)
+        .zip(WORDS)
+        .find(|(_, word)| rest.starts_with(word.as_bytes()))
+        .map(|(digit, _)| digit)
+}
+
+fn parse_line_regex(
+    line: &str,
+) -> miette::Result<u32, AocError> {
     let forward_pattern = "1|2|3|4|5|6|7|8|9|0|one|two|three|four|five|six|seven|eight|nine|zero";
     let regex_forward =
         Regex::new(forward_pattern).unwrap();
+    let no_digit = || AocError::NoDigit(line.to_string());
     let first = str_to_u32(
         regex_forward
             .find(line)
-            .expect("Should find something")
+            .ok_or_else(no_digit)?
             .as_str(),
     );
 
@@ -47,7 +173,7 @@ fn parse_line(line: &str) -> miette::Result<u32, AocError> {
     let last = str_to_u32(
         regex_backward
             .find(&reversed_line)
-            .expect("Should find something")
+            .ok_or_else(no_digit)?
             .as_str()
             .chars()
             .rev()
@@ -78,6 +204,12 @@ fn str_to_u32(s: &str) -> u32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::variants::{
+        assert_variants, assert_variants_agree_on_input,
+    };
+    use aoc_common::{
+        lines::assert_all_formats, params::Params as _,
+    };
     use rstest::rstest;
 
     #[rstest]
@@ -89,11 +221,24 @@ mod tests {
     #[case("zoneight234", 14)]
     #[case("7pqrstsixteen", 76)]
     #[case("fivezg8jmf6hrxnhgxxttwoneg", 51)]
+    #[case("eightwo", 82)]
+    #[case("oneight", 18)]
     fn line_test(
         #[case] line: &str,
         #[case] expected: u32,
     ) {
-        assert_eq!(expected, parse_line(line).unwrap())
+        assert_eq!(
+            expected,
+            parse_line(&ENGLISH, line).unwrap()
+        );
+        assert_eq!(
+            expected,
+            parse_line_bytes(line).unwrap()
+        );
+        assert_eq!(
+            expected,
+            parse_line_regex(line).unwrap()
+        );
     }
 
     #[rstest]
@@ -118,6 +263,62 @@ zoneight234
         #[case] input: &str,
         #[case] expected: u32,
     ) {
-        assert_eq!(process(input).unwrap(), expected);
+        assert_variants(VARIANTS, input, expected);
+        assert_all_formats(
+            |input| process_reader(input.as_bytes()),
+            input,
+            expected,
+        );
+        assert_eq!(
+            process_reader(input.as_bytes()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_process_with() -> miette::Result<()> {
+        let input = "zweiundvierzig
+sechsundzwanzig
+2neun";
+        let params = Params::with(["vocabulary=german"])?;
+        assert_eq!(
+            24 + 66 + 29,
+            process_with(input, &params)?
+        );
+        assert_all_formats(
+            |input| process_with(input, &params),
+            input,
+            119,
+        );
+        // "un" is French, but inside "neun" it
+        // isn't a word of its own
+        let params =
+            Params::with(["vocabulary=german+french"])?;
+        assert_eq!(
+            24 + 61 + 29,
+            process_with(input, &params)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_digit() {
+        for variant in VARIANTS {
+            assert!(
+                matches!(
+                    (variant.process)("one\nnothing here"),
+                    Err(AocError::NoDigit(line)) if line == "nothing here"
+                ),
+                "{variant}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_variants_agree_on_input() {
+        assert_variants_agree_on_input(
+            VARIANTS,
+            env!("CARGO_PKG_NAME"),
+        );
     }
 }
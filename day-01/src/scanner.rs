@@ -0,0 +1,240 @@
+//! An Aho-Corasick automaton over the words of a
+//! [`Vocabulary`](crate::vocabulary::Vocabulary),
+//! finding the first and last one in a line in a
+//! single pass.
+//!
+//! The automaton keeps following words that
+//! overlap, so `twone` gives both `two` and `one`
+//! without going back over the line. A word
+//! inside a longer one, such as `un` at the end
+//! of `neun`, doesn't count on its own.
+use std::collections::VecDeque;
+
+/// A digit or digit word found in a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    /// Byte offset of the first byte
+    pub start: usize,
+    /// Length in bytes
+    pub len: usize,
+    pub value: u32,
+}
+
+/// The longest word ending in a state, as its
+/// length and value
+#[derive(Debug, Clone, Copy)]
+struct Output {
+    len: usize,
+    value: u32,
+}
+
+const MISSING: u32 = u32::MAX;
+
+pub struct Scanner {
+    /// Bytes that appear in a word each get their
+    /// own class, the rest share class 0
+    classes: [u8; 256],
+    /// `next[state * stride + class]`, with
+    /// failure links already followed so every
+    /// byte has a transition
+    next: Vec<u16>,
+    stride: usize,
+    longest: Vec<Option<Output>>,
+}
+
+impl Scanner {
+    /// An automaton matching each of `words`,
+    /// which give their values
+    pub fn new<'a>(
+        words: impl IntoIterator<Item = (&'a str, u32)>,
+    ) -> Self {
+        let mut next = vec![[MISSING; 256]];
+        let mut longest = vec![None];
+        for (word, value) in words {
+            let mut state = 0;
+            for &byte in word.as_bytes() {
+                let byte = usize::from(byte);
+                if next[state][byte] == MISSING {
+                    next[state][byte] = next.len() as u32;
+                    next.push([MISSING; 256]);
+                    longest.push(None);
+                }
+                state = next[state][byte] as usize;
+            }
+            longest[state] = Some(Output {
+                len: word.len(),
+                value,
+            });
+        }
+        let mut classes = [0; 256];
+        let mut stride = 1;
+        for byte in 0..256 {
+            if next.iter().any(|row| row[byte] != MISSING) {
+                classes[byte] = stride as u8;
+                stride += 1;
+            }
+        }
+        assert!(
+            next.len() <= usize::from(u16::MAX),
+            "too many words for the scanner"
+        );
+
+        // breadth first, so a state's failure link
+        // is finished before its children need it
+        let mut fail = vec![0; next.len()];
+        let mut queue = VecDeque::new();
+        for slot in &mut next[0] {
+            match *slot {
+                MISSING => *slot = 0,
+                child => queue.push_back(child as usize),
+            }
+        }
+        while let Some(state) = queue.pop_front() {
+            let failed = fail[state];
+            // a state's own word is longer than any
+            // word its failure link ends in
+            if longest[state].is_none() {
+                longest[state] = longest[failed];
+            }
+            let fallback = next[failed];
+            for (slot, &fallback) in
+                next[state].iter_mut().zip(&fallback)
+            {
+                match *slot {
+                    MISSING => *slot = fallback,
+                    child => {
+                        fail[child as usize] =
+                            fallback as usize;
+                        queue.push_back(child as usize);
+                    }
+                }
+            }
+        }
+
+        // one column per class keeps the table small
+        // enough to stay in cache
+        let mut compact = vec![0; next.len() * stride];
+        for (state, row) in next.iter().enumerate() {
+            for (byte, &class) in classes.iter().enumerate()
+            {
+                compact
+                    [state * stride + usize::from(class)] =
+                    row[byte] as u16;
+            }
+        }
+        Self {
+            classes,
+            next: compact,
+            stride,
+            longest,
+        }
+    }
+
+    /// The tokens that start furthest left and
+    /// end furthest right in `line`, each the
+    /// longest word there
+    pub fn first_and_last(
+        &self,
+        line: &[u8],
+    ) -> Option<(Token, Token)> {
+        let mut first: Option<Token> = None;
+        let mut last: Option<Token> = None;
+        let mut state = 0;
+        for (i, &byte) in line.iter().enumerate() {
+            let class = self.classes[usize::from(byte)];
+            state = usize::from(
+                self.next[state * self.stride
+                    + usize::from(class)],
+            );
+            let Some(Output { len, value }) =
+                self.longest[state]
+            else {
+                continue;
+            };
+            let token = Token {
+                start: i + 1 - len,
+                len,
+                value,
+            };
+            // a later token starting at the same
+            // place is longer
+            if first.is_none_or(|first| {
+                token.start <= first.start
+            }) {
+                first = Some(token);
+            }
+            last = Some(token);
+        }
+        first.zip(last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(
+        start: usize,
+        len: usize,
+        value: u32,
+    ) -> Token {
+        Token { start, len, value }
+    }
+
+    #[test]
+    fn test_overlapping_words() {
+        let scanner = Scanner::new([
+            ("one", 1),
+            ("two", 2),
+            ("seven", 7),
+            ("eight", 8),
+            ("nine", 9),
+        ]);
+        assert_eq!(
+            Some((token(0, 3, 2), token(2, 3, 1))),
+            scanner.first_and_last(b"twone")
+        );
+        assert_eq!(
+            Some((token(0, 5, 8), token(4, 3, 2))),
+            scanner.first_and_last(b"eightwo")
+        );
+        assert_eq!(
+            Some((token(1, 4, 9), token(1, 4, 9))),
+            scanner.first_and_last(b"xninex")
+        );
+        // "seve" fails over to "e" and on to "eight"
+        assert_eq!(
+            Some((token(3, 5, 8), token(3, 5, 8))),
+            scanner.first_and_last(b"seveight")
+        );
+        assert_eq!(None, scanner.first_and_last(b"abc"));
+        assert_eq!(None, scanner.first_and_last(b""));
+    }
+
+    #[test]
+    fn test_nested_words() {
+        // "ab" and "b" are inside "xaby"
+        let scanner = Scanner::new([
+            ("xaby", 1),
+            ("ab", 2),
+            ("b", 3),
+        ]);
+        assert_eq!(
+            Some((token(0, 4, 1), token(0, 4, 1))),
+            scanner.first_and_last(b"xaby")
+        );
+        assert_eq!(
+            Some((token(1, 2, 2), token(1, 2, 2))),
+            scanner.first_and_last(b"xab")
+        );
+        assert_eq!(
+            Some((token(1, 2, 2), token(3, 1, 3))),
+            scanner.first_and_last(b"xabb")
+        );
+        let scanner = Scanner::new([("i", 1), ("ii", 2)]);
+        assert_eq!(
+            Some((token(0, 2, 2), token(0, 2, 2))),
+            scanner.first_and_last(b"ii")
+        );
+    }
+}
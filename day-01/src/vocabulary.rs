@@ -0,0 +1,487 @@
+//! The words a calibration line may spell its
+//! digits with, either built in or loaded from a
+//! TOML or JSON file such as
+//!
+//! ```toml
+//! # the digits 0 to 9 count too unless this is false
+//! digits = true
+//!
+//! [words]
+//! een = 1
+//! twee = 2
+//! ```
+//!
+//! Several vocabularies can be joined with `+`,
+//! as in `english+german`, for lines that mix
+//! languages.
+use std::{collections::BTreeMap, fs, path::Path};
+
+use miette::Diagnostic;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::scanner::Scanner;
+
+#[derive(Error, Diagnostic, Debug)]
+pub enum VocabularyError {
+    #[error("no vocabulary called `{name}`")]
+    #[diagnostic(
+        code(aoc::vocabulary),
+        help("use one of {known}, several joined with `+`, or a `.toml` or `.json` file")
+    )]
+    Unknown { name: String, known: String },
+
+    #[error("couldn't read {path}")]
+    #[diagnostic(code(aoc::vocabulary))]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("{path} isn't a valid vocabulary")]
+    #[diagnostic(code(aoc::vocabulary))]
+    Toml {
+        path: String,
+        source: toml::de::Error,
+    },
+
+    #[error("{path} isn't a valid vocabulary")]
+    #[diagnostic(code(aoc::vocabulary))]
+    Json {
+        path: String,
+        source: serde_json::Error,
+    },
+
+    #[error("`{word}` is {value}, which isn't a digit")]
+    #[diagnostic(code(aoc::vocabulary))]
+    NotADigit { word: String, value: u32 },
+
+    #[error("`{word}` is both {first} and {second}")]
+    #[diagnostic(
+        code(aoc::vocabulary),
+        help("a word joined from several vocabularies must have the same value in each")
+    )]
+    Conflict {
+        word: String,
+        first: u32,
+        second: u32,
+    },
+
+    #[error("a vocabulary can't have an empty word")]
+    #[diagnostic(code(aoc::vocabulary))]
+    EmptyWord,
+}
+
+const ENGLISH: &[(&str, u32)] = &[
+    ("zero", 0),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+const GERMAN: &[(&str, u32)] = &[
+    ("null", 0),
+    ("eins", 1),
+    ("zwei", 2),
+    ("drei", 3),
+    ("vier", 4),
+    ("fünf", 5),
+    ("sechs", 6),
+    ("sieben", 7),
+    ("acht", 8),
+    ("neun", 9),
+];
+
+const FRENCH: &[(&str, u32)] = &[
+    ("zéro", 0),
+    ("un", 1),
+    ("deux", 2),
+    ("trois", 3),
+    ("quatre", 4),
+    ("cinq", 5),
+    ("six", 6),
+    ("sept", 7),
+    ("huit", 8),
+    ("neuf", 9),
+];
+
+/// There's no numeral for zero
+const ROMAN: &[(&str, u32)] = &[
+    ("I", 1),
+    ("II", 2),
+    ("III", 3),
+    ("IV", 4),
+    ("V", 5),
+    ("VI", 6),
+    ("VII", 7),
+    ("VIII", 8),
+    ("IX", 9),
+];
+
+const BUILT_IN: [(&str, &[(&str, u32)]); 4] = [
+    ("english", ENGLISH),
+    ("german", GERMAN),
+    ("french", FRENCH),
+    ("roman", ROMAN),
+];
+
+fn default_digits() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Vocabulary {
+    /// Whether `0` to `9` count as well as the
+    /// words
+    #[serde(default = "default_digits")]
+    pub digits: bool,
+    pub words: BTreeMap<String, u32>,
+}
+
+impl Vocabulary {
+    fn built_in(words: &[(&str, u32)]) -> Self {
+        Self {
+            digits: true,
+            words: words
+                .iter()
+                .map(|&(word, value)| {
+                    (word.to_string(), value)
+                })
+                .collect(),
+        }
+    }
+
+    /// The puzzle's vocabulary
+    pub fn english() -> Self {
+        Self::built_in(ENGLISH)
+    }
+
+    pub fn german() -> Self {
+        Self::built_in(GERMAN)
+    }
+
+    pub fn french() -> Self {
+        Self::built_in(FRENCH)
+    }
+
+    /// Upper case numerals from `I` to `IX`
+    pub fn roman() -> Self {
+        Self::built_in(ROMAN)
+    }
+
+    /// A built-in vocabulary by name, a path to a
+    /// `.toml` or `.json` file, or several of
+    /// either joined with `+`
+    pub fn find(
+        spec: &str,
+    ) -> Result<Self, VocabularyError> {
+        spec.split('+')
+            .map(|part| {
+                let part = part.trim();
+                match BUILT_IN
+                    .iter()
+                    .find(|(name, _)| *name == part)
+                {
+                    Some((_, words)) => {
+                        Ok(Self::built_in(words))
+                    }
+                    None if part.ends_with(".toml")
+                        || part.ends_with(".json") =>
+                    {
+                        Self::load(part)
+                    }
+                    None => Err(Self::unknown(part)),
+                }
+            })
+            .try_fold(
+                None,
+                |joined: Option<Self>, next| {
+                    Ok(Some(match joined {
+                        Some(joined) => {
+                            joined.join(next?)?
+                        }
+                        None => next?,
+                    }))
+                },
+            )?
+            .ok_or_else(|| Self::unknown(spec))
+    }
+
+    fn unknown(name: &str) -> VocabularyError {
+        VocabularyError::Unknown {
+            name: name.to_string(),
+            known: BUILT_IN
+                .map(|(name, _)| format!("`{name}`"))
+                .join(", "),
+        }
+    }
+
+    /// Load a vocabulary from a TOML file, or
+    /// JSON when the path ends in `.json`, and
+    /// check every word is a digit from 0 to 9
+    pub fn load(
+        path: impl AsRef<Path>,
+    ) -> Result<Self, VocabularyError> {
+        let path = path.as_ref();
+        let name = path.display().to_string();
+        let text =
+            fs::read_to_string(path).map_err(|source| {
+                VocabularyError::Io {
+                    path: name.clone(),
+                    source,
+                }
+            })?;
+        if path.extension().is_some_and(|ext| ext == "json")
+        {
+            Self::from_json(&text).map_err(|source| {
+                VocabularyError::Json { path: name, source }
+            })?
+        } else {
+            Self::from_toml(&text).map_err(|source| {
+                VocabularyError::Toml { path: name, source }
+            })?
+        }
+        .validated()
+    }
+
+    fn from_toml(
+        text: &str,
+    ) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    fn from_json(
+        text: &str,
+    ) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(text)
+    }
+
+    /// Check every word is non-empty with a value
+    /// from 0 to 9
+    fn validated(self) -> Result<Self, VocabularyError> {
+        if self.words.contains_key("") {
+            return Err(VocabularyError::EmptyWord);
+        }
+        if let Some((word, &value)) =
+            self.words.iter().find(|(_, &value)| value > 9)
+        {
+            return Err(VocabularyError::NotADigit {
+                word: word.clone(),
+                value,
+            });
+        }
+        Ok(self)
+    }
+
+    /// Every word from both vocabularies, which
+    /// must agree on the value of any word they
+    /// share
+    pub fn join(
+        mut self,
+        other: Self,
+    ) -> Result<Self, VocabularyError> {
+        for (word, value) in other.words {
+            match self.words.get(&word) {
+                Some(&first) if first != value => {
+                    return Err(
+                        VocabularyError::Conflict {
+                            word,
+                            first,
+                            second: value,
+                        },
+                    );
+                }
+                _ => {
+                    self.words.insert(word, value);
+                }
+            }
+        }
+        self.digits |= other.digits;
+        Ok(self)
+    }
+
+    /// Every word with its value, and the digits
+    /// when they count
+    pub fn entries(
+        &self,
+    ) -> impl Iterator<Item = (&str, u32)> + '_ {
+        const DIGITS: [&str; 10] = [
+            "0", "1", "2", "3", "4", "5", "6", "7", "8",
+            "9",
+        ];
+        DIGITS
+            .into_iter()
+            .zip(0..)
+            .filter(|_| self.digits)
+            .chain(self.words.iter().map(
+                |(word, &value)| (word.as_str(), value),
+            ))
+    }
+
+    pub fn scanner(&self) -> Scanner {
+        Scanner::new(self.entries())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn calibrate(
+        vocabulary: &Vocabulary,
+        line: &str,
+    ) -> Option<u32> {
+        vocabulary
+            .scanner()
+            .first_and_last(line.as_bytes())
+            .map(|(first, last)| {
+                first.value * 10 + last.value
+            })
+    }
+
+    #[rstest]
+    #[case("english", "xtwone3four", 24)]
+    #[case("english", "eightwo", 82)]
+    #[case("german", "zweidrei", 23)]
+    #[case("german", "7achtneunx", 79)]
+    #[case("german", "fünfsechs", 56)]
+    #[case("french", "deuxtrois", 23)]
+    #[case("french", "zérotrois", 3)]
+    // a misspelt "quatre" ending in "trois"
+    #[case("french", "huitunquatrois", 83)]
+    #[case("roman", "xVIIIy", 88)]
+    #[case("roman", "IVy", 44)]
+    #[case("roman", "aIXbVIc", 96)]
+    // "un" inside "neun" doesn't count on its own
+    #[case("german+french", "unxneun", 19)]
+    #[case("german+french", "neunx", 99)]
+    // "six" is French and English
+    #[case("english+french", "sixone", 61)]
+    // "eins", "seven" and "null" share letters
+    #[case("english+german", "einsevenull", 10)]
+    fn test_vocabulary(
+        #[case] spec: &str,
+        #[case] line: &str,
+        #[case] expected: u32,
+    ) -> Result<(), VocabularyError> {
+        let vocabulary = Vocabulary::find(spec)?;
+        assert_eq!(
+            Some(expected),
+            calibrate(&vocabulary, line)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_digits_are_optional() -> miette::Result<()> {
+        let vocabulary = Vocabulary::from_toml(
+            r#"
+digits = false
+[words]
+een = 1
+twee = 2
+"#,
+        )
+        .map_err(miette::Report::msg)?;
+        assert_eq!(
+            Some(12),
+            calibrate(&vocabulary, "3een4twee5")
+        );
+        assert_eq!(None, calibrate(&vocabulary, "345"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_json() -> miette::Result<()> {
+        let vocabulary = Vocabulary::from_json(
+            r#"{"words": {"uno": 1, "dos": 2}}"#,
+        )
+        .map_err(miette::Report::msg)?;
+        assert!(vocabulary.digits);
+        assert_eq!(
+            Some(21),
+            calibrate(&vocabulary, "dos9uno")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_load() -> miette::Result<()> {
+        let dir = std::env::temp_dir().join(format!(
+            "day-01-vocabulary-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir)
+            .map_err(miette::Report::msg)?;
+        let toml = dir.join("dutch.toml");
+        fs::write(&toml, "[words]\neen = 1\ntwee = 2\n")
+            .map_err(miette::Report::msg)?;
+        let json = dir.join("spanish.json");
+        fs::write(&json, r#"{"words": {"uno": 1}}"#)
+            .map_err(miette::Report::msg)?;
+
+        let spec = format!(
+            "{}+{}+english",
+            toml.display(),
+            json.display()
+        );
+        let vocabulary = Vocabulary::find(&spec)?;
+        assert_eq!(
+            Some(11),
+            calibrate(&vocabulary, "eenuno")
+        );
+        assert_eq!(
+            Some(29),
+            calibrate(&vocabulary, "tweenine")
+        );
+
+        fs::write(&toml, "[words]\neen = 10\n")
+            .map_err(miette::Report::msg)?;
+        assert!(matches!(
+            Vocabulary::load(&toml),
+            Err(VocabularyError::NotADigit {
+                value: 10,
+                ..
+            })
+        ));
+        fs::write(&toml, "[word]\neen = 1\n")
+            .map_err(miette::Report::msg)?;
+        assert!(matches!(
+            Vocabulary::load(&toml),
+            Err(VocabularyError::Toml { .. })
+        ));
+        fs::remove_dir_all(&dir)
+            .map_err(miette::Report::msg)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_errors() {
+        assert!(matches!(
+            Vocabulary::find("klingon"),
+            Err(VocabularyError::Unknown { name, .. }) if name == "klingon"
+        ));
+        assert!(matches!(
+            Vocabulary::find("missing.toml"),
+            Err(VocabularyError::Io { .. })
+        ));
+        let conflict = Vocabulary::english()
+            .join(Vocabulary::built_in(&[("one", 2)]));
+        assert!(matches!(
+            conflict,
+            Err(VocabularyError::Conflict {
+                first: 1,
+                second: 2,
+                ..
+            })
+        ));
+    }
+}
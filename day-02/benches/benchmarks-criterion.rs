@@ -4,23 +4,31 @@ use criterion::{
 use day_02::*;
 
 fn criterion_benchmark_part1(c: &mut Criterion) {
-    let input = include_str!("../input1.txt");
+    let input =
+        aoc_common::input::load_day(env!("CARGO_PKG_NAME"))
+            .unwrap();
 
     let mut group = c.benchmark_group("day_02::part1");
-    group.bench_with_input("part1", input, |b, input| {
-        b.iter(|| part1::process(input))
-    });
+    group.bench_with_input(
+        "part1",
+        input.as_str(),
+        |b, input| b.iter(|| part1::process(input)),
+    );
 
     group.finish();
 }
 
 fn criterion_benchmark_part2(c: &mut Criterion) {
-    let input = include_str!("../input2.txt");
+    let input =
+        aoc_common::input::load_day(env!("CARGO_PKG_NAME"))
+            .unwrap();
 
     let mut group = c.benchmark_group("day_02::part2");
-    group.bench_with_input("part2", input, |b, input| {
-        b.iter(|| part2::process(input))
-    });
+    group.bench_with_input(
+        "part2",
+        input.as_str(),
+        |b, input| b.iter(|| part2::process(input)),
+    );
 
     group.finish();
 }
@@ -0,0 +1,307 @@
+//! Questions about games for any bag of cubes,
+//! including colours the puzzle doesn't mention.
+//! A bag is written like a round, as in `12 red,
+//! 13 green, 14 blue, 2 purple`.
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use aoc_common::parse::{parse_all, ParseError};
+use serde::{Serialize, Serializer};
+
+use crate::game::{self, Colour, Game};
+
+/// How many cubes of each colour are in a bag.
+/// Colours that aren't listed have none.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bag {
+    pub cubes: BTreeMap<Colour, usize>,
+}
+
+impl Bag {
+    pub fn new(
+        cubes: impl IntoIterator<Item = (Colour, usize)>,
+    ) -> Self {
+        Self {
+            cubes: cubes.into_iter().collect(),
+        }
+    }
+
+    pub fn count(&self, colour: &Colour) -> usize {
+        self.cubes.get(colour).copied().unwrap_or(0)
+    }
+
+    /// Whether every round of `game` could have
+    /// been drawn from this bag
+    pub fn can_play(&self, game: &Game) -> bool {
+        game.rounds.iter().all(|round| {
+            round.cubes.iter().all(|cubes| {
+                round.count(&cubes.colour)
+                    <= self.count(&cubes.colour)
+            })
+        })
+    }
+
+    /// The product of the counts of every colour
+    /// in the bag
+    pub fn power(&self) -> usize {
+        self.cubes.values().product()
+    }
+
+    /// The most of each colour from either bag,
+    /// the smallest bag holding both
+    pub fn union(mut self, other: &Bag) -> Bag {
+        for (colour, &count) in &other.cubes {
+            let most = self
+                .cubes
+                .entry(colour.clone())
+                .or_insert(0);
+            *most = count.max(*most);
+        }
+        self
+    }
+}
+
+impl FromStr for Bag {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let round = parse_all(text, game::round)?;
+        Ok(Bag::new(round.cubes.into_iter().map(
+            |cubes| (cubes.colour, cubes.count),
+        )))
+    }
+}
+
+impl fmt::Display for Bag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cubes = self
+            .cubes
+            .iter()
+            .map(|(colour, count)| {
+                format!("{count} {colour}")
+            })
+            .collect::<Vec<String>>();
+        f.write_str(&cubes.join(", "))
+    }
+}
+
+/// Written as a map from colour name to count
+impl Serialize for Bag {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.cubes.iter().map(|(colour, count)| {
+                (colour.name(), count)
+            }),
+        )
+    }
+}
+
+/// The ids of the games `bag` could have played
+pub fn possible_games(
+    games: &[Game],
+    bag: &Bag,
+) -> Vec<u32> {
+    games
+        .iter()
+        .filter(|game| bag.can_play(game))
+        .map(|game| game.id)
+        .collect()
+}
+
+/// The smallest bag every game could have been
+/// played with
+pub fn minimum_bag_for_all(games: &[Game]) -> Bag {
+    games.iter().fold(Bag::default(), |bag, game| {
+        bag.union(&game.minimum_bag())
+    })
+}
+
+/// The sum of each game's minimum bag's power,
+/// which is part 2's answer
+pub fn power_sum(games: &[Game]) -> usize {
+    games
+        .iter()
+        .map(|game| game.minimum_bag().power())
+        .sum()
+}
+
+#[derive(Debug, Serialize)]
+pub struct GameReport {
+    pub id: u32,
+    pub minimum_bag: Bag,
+    pub power: usize,
+    pub possible: bool,
+}
+
+/// Everything the queries can say about the games
+/// and a bag
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub bag: Bag,
+    pub possible_games: Vec<u32>,
+    /// Part 1's answer for the puzzle's bag
+    pub possible_id_sum: u32,
+    pub games: Vec<GameReport>,
+    pub minimum_bag_for_all: Bag,
+    /// Part 2's answer
+    pub power_sum: usize,
+}
+
+impl Report {
+    pub fn new(games: &[Game], bag: Bag) -> Self {
+        let possible_games = possible_games(games, &bag);
+        Report {
+            possible_id_sum: possible_games.iter().sum(),
+            possible_games,
+            games: games
+                .iter()
+                .map(|game| {
+                    let minimum_bag = game.minimum_bag();
+                    GameReport {
+                        id: game.id,
+                        power: minimum_bag.power(),
+                        minimum_bag,
+                        possible: bag.can_play(game),
+                    }
+                })
+                .collect(),
+            minimum_bag_for_all: minimum_bag_for_all(games),
+            power_sum: power_sum(games),
+            bag,
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "bag: {}", self.bag)?;
+        let width = self
+            .games
+            .iter()
+            .map(|game| game.minimum_bag.to_string().len())
+            .max()
+            .unwrap_or(0)
+            .max("fewest cubes".len());
+        writeln!(
+            f,
+            "game  possible  {:<width$}  power",
+            "fewest cubes"
+        )?;
+        for game in &self.games {
+            writeln!(
+                f,
+                "{:>4}  {:<8}  {:<width$}  {:>5}",
+                game.id,
+                if game.possible { "yes" } else { "no" },
+                game.minimum_bag.to_string(),
+                game.power
+            )?;
+        }
+        writeln!(
+            f,
+            "{} possible games, ids summing to {}",
+            self.possible_games.len(),
+            self.possible_id_sum
+        )?;
+        writeln!(
+            f,
+            "fewest cubes for every game: {}",
+            self.minimum_bag_for_all
+        )?;
+        writeln!(f, "power sum: {}", self.power_sum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::parse_games;
+
+    const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[test]
+    fn test_queries() -> Result<(), ParseError> {
+        let games = parse_games(INPUT)?;
+        let bag: Bag =
+            "12 red, 13 green, 14 blue".parse()?;
+        assert_eq!(
+            vec![1, 2, 5],
+            possible_games(&games, &bag)
+        );
+        assert_eq!(
+            "4 red, 2 green, 6 blue",
+            games[0].minimum_bag().to_string()
+        );
+        assert_eq!(
+            "20 red, 13 green, 15 blue",
+            minimum_bag_for_all(&games).to_string()
+        );
+        assert_eq!(2286, power_sum(&games));
+        let everything = minimum_bag_for_all(&games);
+        assert_eq!(
+            vec![1, 2, 3, 4, 5],
+            possible_games(&games, &everything)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_other_colours() -> Result<(), ParseError> {
+        let games = parse_games(
+            "Game 1: 1 red, 2 purple\nGame 2: 3 red",
+        )?;
+        // a colour the bag doesn't list has no cubes
+        let bag: Bag = "5 red".parse()?;
+        assert_eq!(vec![2], possible_games(&games, &bag));
+        let bag: Bag = "5 red, 2 purple".parse()?;
+        assert_eq!(
+            vec![1, 2],
+            possible_games(&games, &bag)
+        );
+        assert_eq!(
+            Bag::new([
+                (Colour::Red, 3),
+                (Colour::Other("purple".to_string()), 2)
+            ]),
+            minimum_bag_for_all(&games)
+        );
+        assert_eq!(2 + 3, power_sum(&games));
+        Ok(())
+    }
+
+    #[test]
+    fn test_report() -> Result<(), ParseError> {
+        let games = parse_games(INPUT)?;
+        let report = Report::new(
+            &games,
+            "12 red, 13 green, 14 blue".parse()?,
+        );
+        assert_eq!(8, report.possible_id_sum);
+        assert_eq!(
+            "bag: 12 red, 13 green, 14 blue
+game  possible  fewest cubes              power
+   1  yes       4 red, 2 green, 6 blue       48
+   2  yes       1 red, 3 green, 4 blue       12
+   3  no        20 red, 13 green, 6 blue   1560
+   4  no        14 red, 3 green, 15 blue    630
+   5  yes       6 red, 3 green, 2 blue       36
+3 possible games, ids summing to 8
+fewest cubes for every game: 20 red, 13 green, 15 blue
+power sum: 2286
+",
+            report.to_string()
+        );
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            serde_json::json!({"red": 20, "green": 13, "blue": 15}),
+            json["minimum_bag_for_all"]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+//! Print which games a bag could have played,
+//! each game's fewest cubes and the power sum, as
+//! a table or with `--json`. The bag is given
+//! with `--bag "12 red, 2 purple"`, or else by
+//! part 1's `--param red=...` overrides.
+use aoc_common::params::{overrides, Params as _};
+use day_02::{
+    bag::{Bag, Report},
+    game::parse_games,
+    part1::Params,
+};
+use miette::IntoDiagnostic;
+
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut json = false;
+    let mut spec = None;
+    let mut rest = vec![];
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--json" {
+            json = true;
+        } else if let Some(value) =
+            arg.strip_prefix("--bag=")
+        {
+            spec = Some(value.to_string());
+        } else if arg == "--bag" {
+            spec = Some(args.next().ok_or_else(|| {
+                miette::miette!("--bag needs a bag such as \"12 red, 13 green\"")
+            })?);
+        } else {
+            rest.push(arg);
+        }
+    }
+    let bag: Bag = match spec {
+        Some(spec) => spec.parse()?,
+        None => (&Params::with(overrides(rest)?)?).into(),
+    };
+    let file = aoc_common::input::load_day(env!(
+        "CARGO_PKG_NAME"
+    ))?;
+    let report = Report::new(&parse_games(&file)?, bag);
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report)
+                .into_diagnostic()?
+        );
+    } else {
+        print!("{report}");
+    }
+    Ok(())
+}
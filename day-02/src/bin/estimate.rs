@@ -0,0 +1,35 @@
+//! Print the most likely bags for every game, as
+//! a table or with `--json`. Takes `--param
+//! bound=...` for the most cubes of a colour to
+//! consider and `--param top=...` for how many
+//! bags to show per game.
+use aoc_common::params::{overrides, Params as _};
+use day_02::{
+    estimate::{estimate, to_table, Params},
+    game::parse_games,
+};
+use miette::IntoDiagnostic;
+
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let (json, args): (Vec<String>, Vec<String>) =
+        std::env::args()
+            .skip(1)
+            .partition(|arg| arg == "--json");
+    let params = Params::with(overrides(args)?)?;
+    let file = aoc_common::input::load_day(env!(
+        "CARGO_PKG_NAME"
+    ))?;
+    let estimates = estimate(&parse_games(&file)?, &params);
+    if json.is_empty() {
+        print!("{}", to_table(&estimates));
+    } else {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&estimates)
+                .into_diagnostic()?
+        );
+    }
+    Ok(())
+}
@@ -1,4 +1,5 @@
-use day_02::part1::process;
+use aoc_common::params::Params as _;
+use day_02::part1::{process_with, Params};
 use miette::Context;
 
 #[cfg(feature = "dhat-heap")]
@@ -13,8 +14,12 @@ fn main() -> miette::Result<()> {
     #[cfg(not(feature = "dhat-heap"))]
     tracing_subscriber::fmt::init();
 
-    let file = include_str!("../../input1.txt");
-    let result = process(file).context("process part 1")?;
+    let params = Params::from_args()?;
+    let file = aoc_common::input::load_day(env!(
+        "CARGO_PKG_NAME"
+    ))?;
+    let result = process_with(&file, &params)
+        .context("process part 1")?;
     println!("{}", result);
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,338 @@
+//! Which bag a game was most likely played with.
+//!
+//! The cubes in a round are drawn from the bag
+//! without replacement and put back before the
+//! next round, so each round follows a
+//! multivariate hypergeometric distribution. A
+//! bag holding `K` cubes of each colour, `N` in
+//! all, shows `k` of each colour in a round of
+//! `n` cubes with probability
+//!
+//! ```text
+//! product over colours of C(K, k) / C(N, n)
+//! ```
+//!
+//! and a game's likelihood is the product over
+//! its rounds. Only the colours the game shows
+//! are considered, since another colour in the
+//! bag can only make what was seen less likely.
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::{
+    bag::Bag,
+    game::{Colour, Game, Round},
+};
+
+aoc_common::params! {
+    /// How far to search for the most likely bag
+    pub struct Params {
+        /// The most cubes of any one colour a
+        /// candidate bag holds
+        bound: usize = 20,
+        /// How many candidates to show per game
+        top: usize = 1,
+    }
+}
+
+/// `ln(n!)` for every `n` up to a limit, so a
+/// binomial coefficient is a few subtractions
+struct LogFactorials(Vec<f64>);
+
+impl LogFactorials {
+    fn new(limit: usize) -> Self {
+        let mut table = vec![0.0; limit + 1];
+        for n in 1..=limit {
+            table[n] = table[n - 1] + (n as f64).ln();
+        }
+        Self(table)
+    }
+
+    /// `ln C(n, k)`, with `k <= n`
+    fn binomial(&self, n: usize, k: usize) -> f64 {
+        self.0[n] - self.0[k] - self.0[n - k]
+    }
+}
+
+/// `ln` of the probability of drawing `round`
+/// from `bag`, or `None` if it can't be drawn
+fn log_round_likelihood(
+    factorials: &LogFactorials,
+    bag: &Bag,
+    round: &Round,
+) -> Option<f64> {
+    let mut shown = Bag::default();
+    for cubes in &round.cubes {
+        *shown
+            .cubes
+            .entry(cubes.colour.clone())
+            .or_insert(0) += cubes.count;
+    }
+    let mut log = 0.0;
+    for (colour, &k) in &shown.cubes {
+        let available = bag.count(colour);
+        if k > available {
+            return None;
+        }
+        log += factorials.binomial(available, k);
+    }
+    let total: usize = bag.cubes.values().sum();
+    let drawn: usize = shown.cubes.values().sum();
+    Some(log - factorials.binomial(total, drawn))
+}
+
+fn log_likelihood(
+    factorials: &LogFactorials,
+    bag: &Bag,
+    game: &Game,
+) -> Option<f64> {
+    game.rounds.iter().try_fold(0.0, |log, round| {
+        Some(
+            log + log_round_likelihood(
+                factorials, bag, round,
+            )?,
+        )
+    })
+}
+
+/// The probability of drawing `round` from
+/// `bag`
+pub fn round_likelihood(bag: &Bag, round: &Round) -> f64 {
+    let factorials =
+        LogFactorials::new(bag.cubes.values().sum());
+    log_round_likelihood(&factorials, bag, round)
+        .map_or(0.0, f64::exp)
+}
+
+/// The probability of every round of `game`
+/// being drawn from `bag`
+pub fn likelihood(bag: &Bag, game: &Game) -> f64 {
+    let factorials =
+        LogFactorials::new(bag.cubes.values().sum());
+    log_likelihood(&factorials, bag, game)
+        .map_or(0.0, f64::exp)
+}
+
+/// A bag the game could have been played with
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Candidate {
+    pub bag: Bag,
+    pub likelihood: f64,
+}
+
+/// Every bag with between the game's minimum and
+/// `bound` cubes of each colour it shows, most
+/// likely first. Bags that are equally likely
+/// keep the order of their counts. Empty if the
+/// game needs more than `bound` of a colour.
+pub fn candidates(
+    game: &Game,
+    bound: usize,
+) -> Vec<Candidate> {
+    let minimum = game.minimum_bag();
+    let colours: Vec<&Colour> =
+        minimum.cubes.keys().collect();
+    let low: Vec<usize> =
+        minimum.cubes.values().copied().collect();
+    if low.iter().any(|&count| count > bound) {
+        return vec![];
+    }
+    let factorials =
+        LogFactorials::new(bound * colours.len());
+
+    let mut candidates = vec![];
+    let mut counts = low.clone();
+    loop {
+        let bag = Bag::new(
+            colours
+                .iter()
+                .copied()
+                .cloned()
+                .zip(counts.iter().copied()),
+        );
+        if let Some(log) =
+            log_likelihood(&factorials, &bag, game)
+        {
+            candidates.push(Candidate {
+                bag,
+                likelihood: log.exp(),
+            });
+        }
+        // count up like an odometer, the last
+        // colour fastest
+        let Some(i) =
+            counts.iter().rposition(|&count| count < bound)
+        else {
+            break;
+        };
+        counts[i] += 1;
+        counts[i + 1..].copy_from_slice(&low[i + 1..]);
+    }
+    candidates.sort_by(|a, b| {
+        b.likelihood.total_cmp(&a.likelihood)
+    });
+    candidates
+}
+
+/// The most likely bags for one game
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Estimate {
+    pub id: u32,
+    pub candidates: Vec<Candidate>,
+}
+
+/// The `params.top` most likely bags for each
+/// game
+pub fn estimate(
+    games: &[Game],
+    params: &Params,
+) -> Vec<Estimate> {
+    games
+        .iter()
+        .map(|game| {
+            let mut candidates =
+                candidates(game, params.bound);
+            candidates.truncate(params.top);
+            Estimate {
+                id: game.id,
+                candidates,
+            }
+        })
+        .collect()
+}
+
+/// One row per candidate, with `-` for a game
+/// that needs more cubes than the bound
+pub fn to_table(estimates: &[Estimate]) -> String {
+    let mut table = String::from("game  likelihood  bag\n");
+    for estimate in estimates {
+        if estimate.candidates.is_empty() {
+            let _ = writeln!(
+                table,
+                "{:>4}  {:>10}  -",
+                estimate.id, "-"
+            );
+        }
+        for candidate in &estimate.candidates {
+            let _ = writeln!(
+                table,
+                "{:>4}  {:>10.4e}  {}",
+                estimate.id,
+                candidate.likelihood,
+                candidate.bag
+            );
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::parse::ParseError;
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 1e-12,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_round_likelihood() -> Result<(), ParseError> {
+        let game: Game =
+            "Game 1: 1 red, 2 green".parse()?;
+        let round = &game.rounds[0];
+        // C(3,1) C(4,2) C(5,0) / C(12,3) = 18 / 220
+        let bag: Bag = "3 red, 4 green, 5 blue".parse()?;
+        assert_close(
+            18.0 / 220.0,
+            round_likelihood(&bag, round),
+        );
+        // C(1,1) C(2,2) / C(3,3)
+        let bag: Bag = "1 red, 2 green".parse()?;
+        assert_close(1.0, round_likelihood(&bag, round));
+        let bag: Bag = "3 red, 1 green".parse()?;
+        assert_close(0.0, round_likelihood(&bag, round));
+        Ok(())
+    }
+
+    #[test]
+    fn test_likelihood() -> Result<(), ParseError> {
+        let game: Game = "Game 1: 2 red; 2 blue".parse()?;
+        // C(2,2) / C(4,2) each round
+        let bag: Bag = "2 red, 2 blue".parse()?;
+        assert_close(1.0 / 36.0, likelihood(&bag, &game));
+        // C(3,2) / C(5,2) then C(2,2) / C(5,2)
+        let bag: Bag = "3 red, 2 blue".parse()?;
+        assert_close(3.0 / 100.0, likelihood(&bag, &game));
+        // C(3,2) / C(6,2) each round
+        let bag: Bag = "3 red, 3 blue".parse()?;
+        assert_close(1.0 / 25.0, likelihood(&bag, &game));
+        // a colour not in the bag can't be drawn
+        let bag: Bag = "3 red".parse()?;
+        assert_close(0.0, likelihood(&bag, &game));
+        Ok(())
+    }
+
+    #[test]
+    fn test_candidates() -> Result<(), ParseError> {
+        let game: Game = "Game 7: 2 red; 2 blue".parse()?;
+        let found = candidates(&game, 3);
+        assert_eq!(
+            vec![
+                "3 red, 3 blue",
+                "2 red, 3 blue",
+                "3 red, 2 blue",
+                "2 red, 2 blue"
+            ],
+            found
+                .iter()
+                .map(|candidate| candidate.bag.to_string())
+                .collect::<Vec<String>>()
+        );
+        assert_close(1.0 / 25.0, found[0].likelihood);
+        assert_close(3.0 / 100.0, found[1].likelihood);
+        assert_close(1.0 / 36.0, found[3].likelihood);
+        assert!(candidates(&game, 1).is_empty());
+
+        let estimates =
+            estimate(&[game], &Params { bound: 3, top: 2 });
+        assert_eq!(7, estimates[0].id);
+        assert_eq!(found[..2], estimates[0].candidates);
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_colour() -> Result<(), ParseError> {
+        // a bag of only red always shows red
+        let game: Game = "Game 1: 4 red; 1 red".parse()?;
+        let found = candidates(&game, 6);
+        assert_eq!(3, found.len());
+        assert!(found
+            .iter()
+            .all(|candidate| candidate.likelihood == 1.0));
+        assert_eq!("4 red", found[0].bag.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_table() -> Result<(), ParseError> {
+        let games = crate::game::parse_games(
+            "Game 1: 2 red; 2 blue\nGame 2: 5 red",
+        )?;
+        assert_eq!(
+            "game  likelihood  bag
+   1   4.0000e-2  3 red, 3 blue
+   1   3.0000e-2  2 red, 3 blue
+   2           -  -
+",
+            to_table(&estimate(
+                &games,
+                &Params { bound: 3, top: 2 }
+            ))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+//! A game record such as `Game 3: 8 green, 6
+//! blue; 5 blue, 4 red`, parsed once into a
+//! [`Game`] and shared by both parts.
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use aoc_common::parse::{
+    integer, lines, parse_all, PResult, ParseError,
+};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alpha1, char, space0, space1},
+    combinator::{cut, map},
+    error::context,
+    multi::many0,
+    sequence::{pair, preceded, separated_pair, tuple},
+};
+
+use crate::bag::Bag;
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+pub enum Colour {
+    Red,
+    Green,
+    Blue,
+    /// Any colour the puzzle doesn't mention
+    Other(String),
+}
+
+impl Colour {
+    pub fn name(&self) -> &str {
+        match self {
+            Colour::Red => "red",
+            Colour::Green => "green",
+            Colour::Blue => "blue",
+            Colour::Other(name) => name,
+        }
+    }
+}
+
+impl FromStr for Colour {
+    type Err = Infallible;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(match name {
+            "red" => Colour::Red,
+            "green" => Colour::Green,
+            "blue" => Colour::Blue,
+            other => Colour::Other(other.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Cubes of one colour shown in a round, such as
+/// `3 blue`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cubes {
+    pub count: usize,
+    pub colour: Colour,
+}
+
+/// A handful of cubes taken from the bag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub cubes: Vec<Cubes>,
+}
+
+impl Round {
+    /// How many cubes of `colour` were shown
+    pub fn count(&self, colour: &Colour) -> usize {
+        self.cubes
+            .iter()
+            .filter(|cubes| &cubes.colour == colour)
+            .map(|cubes| cubes.count)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub rounds: Vec<Round>,
+}
+
+impl Game {
+    /// Parse a single line
+    pub fn parse(line: &str) -> Result<Self, ParseError> {
+        parse_all(line, game)
+    }
+
+    /// The most cubes of `colour` shown in any
+    /// round, which is the fewest the bag can
+    /// hold
+    pub fn max(&self, colour: &Colour) -> usize {
+        self.rounds
+            .iter()
+            .map(|round| round.count(colour))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The fewest cubes of each colour shown that
+    /// the game could have been played with
+    pub fn minimum_bag(&self) -> Bag {
+        self.rounds.iter().fold(
+            Bag::default(),
+            |bag, round| {
+                bag.union(&Bag::new(
+                    round.cubes.iter().map(|cubes| {
+                        (
+                            cubes.colour.clone(),
+                            round.count(&cubes.colour),
+                        )
+                    }),
+                ))
+            },
+        )
+    }
+}
+
+impl FromStr for Game {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        Game::parse(line)
+    }
+}
+
+/// Every game in the input, one per line
+pub fn parse_games(
+    input: &str,
+) -> Result<Vec<Game>, ParseError> {
+    parse_all(input, lines(game))
+}
+
+fn colour(input: &str) -> PResult<'_, Colour> {
+    context(
+        "a colour",
+        map(alpha1, |name: &str| {
+            let Ok(colour) = name.parse();
+            colour
+        }),
+    )(input)
+}
+
+fn cubes(input: &str) -> PResult<'_, Cubes> {
+    map(
+        separated_pair(integer, space1, colour),
+        |(count, colour)| Cubes { count, colour },
+    )(input)
+}
+
+/// One or more items separated by `separator`,
+/// which must be followed by another item
+fn list<'a, O>(
+    separator: char,
+    item: fn(&'a str) -> PResult<'a, O>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<O>> {
+    map(
+        pair(
+            item,
+            many0(preceded(
+                tuple((space0, char(separator), space0)),
+                cut(item),
+            )),
+        ),
+        |(first, rest)| {
+            std::iter::once(first).chain(rest).collect()
+        },
+    )
+}
+
+pub(crate) fn round(input: &str) -> PResult<'_, Round> {
+    map(list(',', cubes), |cubes| Round {
+        cubes,
+    })(input)
+}
+
+/// `Game <id>: ` then rounds separated by `;`
+pub fn game(input: &str) -> PResult<'_, Game> {
+    context(
+        "a game",
+        map(
+            pair(
+                preceded(
+                    pair(tag("Game"), space1),
+                    integer,
+                ),
+                preceded(
+                    pair(char(':'), space0),
+                    cut(list(';', round)),
+                ),
+            ),
+            |(id, rounds)| Game { id, rounds },
+        ),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cubes(count: usize, colour: Colour) -> Cubes {
+        Cubes { count, colour }
+    }
+
+    #[test]
+    fn test_parse() -> Result<(), ParseError> {
+        let game = Game::parse(
+            "Game 12: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+        )?;
+        assert_eq!(12, game.id);
+        assert_eq!(3, game.rounds.len());
+        assert_eq!(
+            vec![
+                cubes(1, Colour::Red),
+                cubes(2, Colour::Green),
+                cubes(6, Colour::Blue)
+            ],
+            game.rounds[1].cubes
+        );
+        assert_eq!(6, game.max(&Colour::Blue));
+        assert_eq!(
+            Bag::new([
+                (Colour::Red, 4),
+                (Colour::Green, 2),
+                (Colour::Blue, 6)
+            ]),
+            game.minimum_bag()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_other_colours() -> Result<(), ParseError> {
+        let game: Game =
+            "Game 1: 2 purple, 1 red; 5 purple".parse()?;
+        let purple = Colour::Other("purple".to_string());
+        assert_eq!(5, game.max(&purple));
+        assert_eq!(0, game.max(&Colour::Green));
+        assert_eq!("purple", purple.to_string());
+        assert_eq!(Ok(Colour::Blue), "blue".parse());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_games() -> Result<(), ParseError> {
+        let games = parse_games(
+            "Game 1: 1 red\r\nGame 2: 2 blue ; 3 green\n\n",
+        )?;
+        assert_eq!(
+            vec![1, 2],
+            games
+                .iter()
+                .map(|game| game.id)
+                .collect::<Vec<u32>>()
+        );
+        assert_eq!(2, games[1].rounds.len());
+
+        let input = "Game 1: 1 red\nGame 2: 2 blue, green";
+        let error = parse_games(input).unwrap_err();
+        assert_eq!(
+            input.rfind("green").unwrap(),
+            error.offset()
+        );
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
+pub mod bag;
 pub mod custom_error;
+pub mod estimate;
+pub mod game;
 
 pub mod part1;
 pub mod part2;
@@ -1,87 +1,88 @@
-use crate::custom_error::AocError;
-use regex::Regex;
-use std::collections::HashMap;
+use crate::{
+    bag::Bag,
+    custom_error::AocError,
+    game::{parse_games, Colour, Game},
+};
+use aoc_common::lines::try_for_each_line;
+use std::io::BufRead;
+
+aoc_common::params! {
+    /// How many cubes of each colour are in the
+    /// bag. As with any [`Bag`], colours the puzzle
+    /// doesn't mention have none, so a game drawing
+    /// them isn't possible.
+    pub struct Params {
+        red: usize = 12,
+        green: usize = 13,
+        blue: usize = 14,
+    }
+}
 
 #[tracing::instrument]
 pub fn process(
     input: &str,
 ) -> miette::Result<i64, AocError> {
-    run(input, 12, 13, 14)
+    process_with(input, &Params::default())
 }
 
-pub fn run(
+/// Same as [`process`] with the bag's contents
+/// given by `params`
+pub fn process_with(
     input: &str,
-    max_red: usize,
-    max_green: usize,
-    max_blue: usize,
+    params: &Params,
 ) -> Result<i64, AocError> {
-    let re_game = Regex::new(r"[,|:|;]").unwrap();
-    let re_game_number =
-        Regex::new(r"^\s*Game\s+(\d+)\s*$").unwrap();
-    let re_number_and_color =
-        Regex::new(r"^\s*(\d+)\s+(\w+)\s*$").unwrap();
-    let mut sum_of_valid_game_number = 0;
-
-    for line in input.lines() {
-        let mut max_color_counts: HashMap<String, usize> =
-            HashMap::new();
-        let mut splits = re_game.split(line);
-        let game_number_part = splits.next().unwrap();
+    let bag = Bag::from(params);
+    Ok(parse_games(input)?
+        .iter()
+        .filter(|game| bag.can_play(game))
+        .map(|game| i64::from(game.id))
+        .sum())
+}
 
-        for part in splits {
-            let caps =
-                re_number_and_color.captures(part).unwrap();
-            let number: usize = caps
-                .get(1)
-                .unwrap()
-                .as_str()
-                .parse()
-                .unwrap();
-            let color: String =
-                caps.get(2).unwrap().as_str().to_string();
+/// Same as [`process`], reading one line at a
+/// time
+pub fn process_reader(
+    reader: impl BufRead,
+) -> Result<i64, AocError> {
+    process_reader_with(reader, &Params::default())
+}
 
-            // Unless we have seen a larger number already,
-            // we insert this color, number pair
-            if let Some(max_number_seen) =
-                max_color_counts.get(&color)
-            {
-                if max_number_seen >= &number {
-                    continue;
-                }
-            }
-            max_color_counts.insert(color, number);
+/// Same as [`process_with`], reading one line at
+/// a time
+pub fn process_reader_with(
+    reader: impl BufRead,
+    params: &Params,
+) -> Result<i64, AocError> {
+    let bag = Bag::from(params);
+    let mut sum_of_valid_game_number = 0;
+    try_for_each_line(reader, |line| {
+        let game = Game::parse(line)?;
+        if bag.can_play(&game) {
+            sum_of_valid_game_number += i64::from(game.id);
         }
+        Ok::<_, AocError>(())
+    })?;
+    Ok(sum_of_valid_game_number)
+}
 
-        // game is possible if red <= 12, green <=13, blue
-        // <=14
-        let game_valid: bool = max_color_counts
-            .get("red")
-            .unwrap_or(&0)
-            <= &max_red
-            && max_color_counts.get("green").unwrap_or(&0)
-                <= &max_green
-            && max_color_counts.get("blue").unwrap_or(&0)
-                <= &max_blue;
-        if game_valid {
-            // replace true with determination of whether
-            // game was possible
-            let game_number: usize = re_game_number
-                .captures(game_number_part)
-                .unwrap()
-                .get(1)
-                .unwrap()
-                .as_str()
-                .parse()
-                .unwrap();
-            sum_of_valid_game_number += game_number;
-        }
+/// The bag `params` describe, for asking
+/// [`bag`](crate::bag) questions about it
+impl From<&Params> for Bag {
+    fn from(params: &Params) -> Self {
+        Bag::new([
+            (Colour::Red, params.red),
+            (Colour::Green, params.green),
+            (Colour::Blue, params.blue),
+        ])
     }
-    Ok(sum_of_valid_game_number as i64)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{
+        lines::assert_all_formats, params::Params as _,
+    };
 
     #[test]
     fn test_process() -> miette::Result<()> {
@@ -91,6 +92,42 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
         assert_eq!(8, process(input)?);
+        assert_all_formats(process, input, 8);
+        assert_eq!(8, process_reader(input.as_bytes())?);
+        assert_all_formats(
+            |input| process_reader(input.as_bytes()),
+            input,
+            8,
+        );
+        let params = Params::with(["red=20", "blue=15"])?;
+        assert_eq!(
+            process_with(input, &params)?,
+            process_reader_with(input.as_bytes(), &params)?
+        );
+        // game 3 needs 20 red and 13 green
+        assert_eq!(
+            11,
+            process_with(
+                input,
+                &Params {
+                    red: 20,
+                    ..Params::default()
+                }
+            )?
+        );
+        assert_eq!(
+            "12 red, 13 green, 14 blue",
+            Bag::from(&Params::default()).to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_other_colours() -> miette::Result<()> {
+        let input =
+            "Game 1: 1 red, 2 purple\nGame 2: 3 red";
+        assert_eq!(2, process(input)?);
+        assert_eq!(2, process_reader(input.as_bytes())?);
         Ok(())
     }
 }
@@ -1,4 +1,5 @@
-use day_03::part2::process;
+use aoc_common::params::Params as _;
+use day_03::part2::{process_with, Params};
 use miette::Context;
 
 #[cfg(feature = "dhat-heap")]
@@ -13,8 +14,12 @@ fn main() -> miette::Result<()> {
     #[cfg(not(feature = "dhat-heap"))]
     tracing_subscriber::fmt::init();
 
-    let file = include_str!("../../input2.txt");
-    let result = process(file).context("process part 2")?;
+    let params = Params::from_args()?;
+    let file = aoc_common::input::load_day(env!(
+        "CARGO_PKG_NAME"
+    ))?;
+    let result = process_with(&file, &params)
+        .context("process part 2")?;
     println!("{}", result);
     Ok(())
 }
@@ -0,0 +1,38 @@
+//! Print the annotated schematic with ANSI
+//! colours, or write it as HTML with `--html
+//! <path>`. Takes the same `--param gear=...` and
+//! `--param radius=...` as part 2.
+use aoc_common::params::{overrides, Params as _};
+use day_03::{part2::Params, render::Annotated};
+use miette::IntoDiagnostic;
+
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut html = None;
+    let mut rest = vec![];
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--html" {
+            html = Some(args.next().ok_or_else(|| {
+                miette::miette!("--html needs a path")
+            })?);
+        } else {
+            rest.push(arg);
+        }
+    }
+    let params = Params::with(overrides(rest)?)?;
+    let file = aoc_common::input::load_day(env!(
+        "CARGO_PKG_NAME"
+    ))?;
+    let annotated = Annotated::new(&file, &params)?;
+    match html {
+        Some(path) => {
+            std::fs::write(&path, annotated.to_html())
+                .into_diagnostic()?;
+            eprintln!("wrote the schematic to `{path}`");
+        }
+        None => print!("{}", annotated.to_ansi()),
+    }
+    Ok(())
+}
@@ -0,0 +1,9 @@
+use day_03::visualize::Highlight;
+
+fn main() -> miette::Result<()> {
+    let file = aoc_common::input::load_day(env!(
+        "CARGO_PKG_NAME"
+    ))?;
+    aoc_common::visualize::main(&Highlight::new(&file)?)?;
+    Ok(())
+}
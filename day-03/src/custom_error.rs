@@ -6,4 +6,8 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+
+    #[error("the number at row {row}, column {column} doesn't fit in a u32")]
+    #[diagnostic(code(aoc::number_too_large))]
+    NumberTooLarge { row: usize, column: usize },
 }
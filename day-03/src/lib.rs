@@ -2,3 +2,6 @@ pub mod custom_error;
 
 pub mod part1;
 pub mod part2;
+pub mod render;
+pub mod schematic;
+pub mod visualize;
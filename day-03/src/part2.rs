@@ -1,129 +1,48 @@
-use crate::custom_error::AocError;
-use glam::u32::UVec2;
-use itertools::Itertools;
+use crate::{custom_error::AocError, schematic::Schematic};
 
-#[derive(Debug, Copy, Clone)]
-struct PartNumber {
-    number: u32,
-    start_position: UVec2,
-}
-
-impl PartNumber {
-    fn new(number: u32, x: u32, y: u32) -> Self {
-        Self {
-            number,
-            start_position: UVec2::new(x, y),
-        }
-    }
-    fn length(&self) -> u32 {
-        self.number.to_string().len() as u32
-    }
-
-    fn is_y_adjacent(&self, position: UVec2) -> bool {
-        let y = self.start_position.y;
-        let y2 = position.y;
-        (y + self.length() >= y2) && (y2 + 1 >= y)
+aoc_common::params! {
+    /// Which symbols are gears and how near their
+    /// numbers must be
+    pub struct Params {
+        gear: char = '*',
+        radius: usize = 1,
     }
 }
 
-#[derive(Debug)]
-struct Gear {
-    position: UVec2,
-}
-
 #[tracing::instrument]
 pub fn process(
     input: &str,
 ) -> miette::Result<u32, AocError> {
-    let mut line1_parts: Vec<PartNumber> = vec![];
-    let mut line2_parts: Vec<PartNumber> = vec![];
-    let mut line2_gears: Vec<Gear> = vec![];
-    let mut part_number_ratio_sum = 0u32;
-    for (line, x) in input.lines().zip(0u32..) {
-        let line0_parts = line1_parts;
-        line1_parts = line2_parts;
-        line2_parts = vec![];
-        let line1_gears = line2_gears;
-        line2_gears = vec![];
-
-        line.chars()
-            .zip(0u32..)
-            .chunk_by(|(c, _)| match c {
-                '.' => '.',
-                '0'..='9' => '#',
-                _ => '*',
-            })
-            .into_iter()
-            .for_each(|(key, mut group)| {
-                match key {
-                    '#' => {
-                        let mut group = group.peekable();
-                        let (_, y) = *group.peek().unwrap();
-                        let number = group
-                            .map(|(c, _)| c)
-                            .collect::<String>()
-                            .parse::<u32>();
-                        line2_parts.push(PartNumber::new(
-                            number.unwrap(),
-                            x,
-                            y,
-                        ));
-                    }
-                    '*' => {
-                        let (_, y) = group.next().unwrap();
-                        line2_gears.push(Gear {
-                            position: UVec2::new(x, y),
-                        });
-                    }
-                    '.' => {}
-                    _ => unreachable!(),
-                };
-            });
-        part_number_ratio_sum += find_part_numbers(
-            &line1_gears,
-            &line0_parts,
-            &line1_parts,
-            &line2_parts,
-        );
-    }
-    part_number_ratio_sum += find_part_numbers(
-        &line2_gears,
-        &line1_parts,
-        &line2_parts,
-        &[],
-    );
-    Ok(part_number_ratio_sum)
+    process_with(input, &Params::default())
 }
 
-fn find_part_numbers(
-    gears: &[Gear],
-    parts0: &[PartNumber],
-    parts1: &[PartNumber],
-    parts2: &[PartNumber],
-) -> u32 {
-    let mut part_number_ratio_sum = 0u32;
-    for gear in gears {
-        let mut adjacent_parts = vec![];
-        for part in parts0
-            .iter()
-            .chain(parts1.iter())
-            .chain(parts2.iter())
-        {
-            if part.is_y_adjacent(gear.position) {
-                adjacent_parts.push(part.number);
-            }
-        }
-        if adjacent_parts.len() == 2 {
-            part_number_ratio_sum +=
-                adjacent_parts.into_iter().product::<u32>();
-        }
-    }
-    part_number_ratio_sum
+/// Same as [`process`] with the gear symbol and
+/// adjacency radius given by `params`
+pub fn process_with(
+    input: &str,
+    params: &Params,
+) -> Result<u32, AocError> {
+    Ok(Schematic::parse(input)?
+        .with_radius(params.radius)
+        .symbols_near_exactly(2)
+        .filter(|(symbol, _)| {
+            symbol.character == params.gear
+        })
+        .map(|(_, numbers)| {
+            numbers
+                .iter()
+                .map(|number| number.value)
+                .product::<u32>()
+        })
+        .sum())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{
+        lines::assert_all_formats, params::Params as _,
+    };
 
     #[test_log::test]
     fn test_process() -> miette::Result<()> {
@@ -138,6 +57,34 @@ mod tests {
 ...$.*....
 .664.598..";
         assert_eq!(467835, process(input)?);
+        assert_all_formats(process, input, 467835);
+        // only 633 is next to the `#`
+        assert_eq!(
+            0,
+            process_with(
+                input,
+                &Params::with(["gear=#"])?
+            )?
+        );
+        // 58 and 755 are two away from the `+`, so
+        // with 592 it has three numbers
+        assert_eq!(
+            0,
+            process_with(
+                input,
+                &Params {
+                    gear: '+',
+                    radius: 2
+                }
+            )?
+        );
+        assert_eq!(
+            633 * 58,
+            process_with(
+                input,
+                &Params::with(["gear=#", "radius=2"])?
+            )?
+        );
         Ok(())
     }
 }
@@ -0,0 +1,485 @@
+//! The whole schematic annotated for reading by
+//! eye: part numbers green, other numbers red and
+//! gears highlighted with their ratios, printed
+//! with ANSI colours or exported as HTML with a
+//! tooltip on every number and symbol.
+//!
+//! The annotations come from the same
+//! [`Schematic`] queries as the parts, so the
+//! totals shown match their answers.
+use std::fmt::Write;
+
+use aoc_common::{
+    lines::input_lines,
+    visualize::{Frame, Rgb},
+};
+
+use crate::{
+    custom_error::AocError,
+    part2::Params,
+    schematic::{Number, Schematic, Symbol},
+};
+
+/// What a cell of the grid is part of, by index
+/// into the schematic's numbers or symbols
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Empty,
+    Part(usize),
+    Number(usize),
+    Gear(usize),
+    Symbol(usize),
+}
+
+/// A gear symbol next to exactly two numbers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gear {
+    pub symbol: Symbol,
+    pub numbers: Vec<Number>,
+    pub ratio: u32,
+}
+
+pub struct Annotated {
+    schematic: Schematic,
+    rows: Vec<Vec<char>>,
+    kinds: Vec<Vec<Kind>>,
+    gears: Vec<Gear>,
+}
+
+impl Annotated {
+    /// Annotate `input` with the gear symbol and
+    /// adjacency radius from part 2's `params`
+    pub fn new(
+        input: &str,
+        params: &Params,
+    ) -> Result<Self, AocError> {
+        let schematic = Schematic::parse(input)?
+            .with_radius(params.radius);
+        let rows = input_lines(input)
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut kinds = rows
+            .iter()
+            .map(|row| vec![Kind::Empty; row.len()])
+            .collect::<Vec<_>>();
+
+        for (i, number) in
+            schematic.numbers().iter().enumerate()
+        {
+            let kind = if schematic
+                .symbols_near(number)
+                .next()
+                .is_some()
+            {
+                Kind::Part(i)
+            } else {
+                Kind::Number(i)
+            };
+            let span = number.span;
+            kinds[span.row][span.start..span.end]
+                .fill(kind);
+        }
+        let mut gears = vec![];
+        for (i, symbol) in
+            schematic.symbols().iter().enumerate()
+        {
+            let numbers = schematic
+                .numbers_near(symbol)
+                .copied()
+                .collect::<Vec<_>>();
+            kinds[symbol.row][symbol.column] =
+                if symbol.character == params.gear
+                    && numbers.len() == 2
+                {
+                    gears.push(Gear {
+                        symbol: *symbol,
+                        ratio: numbers
+                            .iter()
+                            .map(|number| number.value)
+                            .product(),
+                        numbers,
+                    });
+                    Kind::Gear(gears.len() - 1)
+                } else {
+                    Kind::Symbol(i)
+                };
+        }
+        Ok(Self {
+            schematic,
+            rows,
+            kinds,
+            gears,
+        })
+    }
+
+    pub fn gears(&self) -> &[Gear] {
+        &self.gears
+    }
+
+    /// Part 1's answer
+    pub fn part_number_sum(&self) -> u32 {
+        self.schematic
+            .part_numbers()
+            .map(|number| number.value)
+            .sum()
+    }
+
+    /// Part 2's answer
+    pub fn gear_ratio_sum(&self) -> u32 {
+        self.gears.iter().map(|gear| gear.ratio).sum()
+    }
+
+    fn totals(&self) -> String {
+        format!(
+            "part number sum {}, gear ratio sum {}",
+            self.part_number_sum(),
+            self.gear_ratio_sum()
+        )
+    }
+
+    /// The gears in `row` as `a*b=ratio`, shown
+    /// after the row
+    fn row_ratios(&self, row: usize) -> String {
+        self.gears
+            .iter()
+            .filter(|gear| gear.symbol.row == row)
+            .map(|gear| {
+                format!(
+                    "  {}{}{}={}",
+                    gear.numbers[0].value,
+                    gear.symbol.character,
+                    gear.numbers[1].value,
+                    gear.ratio
+                )
+            })
+            .collect()
+    }
+
+    /// The schematic as a [`Frame`], each row
+    /// followed by the ratios of its gears and
+    /// the totals as a caption
+    pub fn to_frame(&self) -> Frame {
+        let text = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.iter().collect::<String>()
+                    + &self.row_ratios(y)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut frame = Frame::from_grid(&text);
+        for (y, row) in self.kinds.iter().enumerate() {
+            for x in row.len()..frame.width() {
+                frame.colour(x, y, Rgb::YELLOW);
+            }
+            for (x, kind) in row.iter().enumerate() {
+                match kind {
+                    Kind::Empty => {}
+                    Kind::Part(_) => {
+                        frame.colour(x, y, Rgb::GREEN)
+                    }
+                    Kind::Number(_) => {
+                        frame.colour(x, y, Rgb::RED)
+                    }
+                    Kind::Gear(_) => {
+                        frame.colour(x, y, Rgb::BLACK);
+                        frame.mark(x, y, Rgb::YELLOW);
+                    }
+                    Kind::Symbol(_) => {
+                        frame.colour(x, y, Rgb::YELLOW)
+                    }
+                }
+            }
+        }
+        frame.caption(self.totals());
+        frame
+    }
+
+    /// The schematic with 24-bit colour escape
+    /// codes
+    pub fn to_ansi(&self) -> String {
+        self.to_frame().to_ansi()
+    }
+
+    /// What a tooltip says about `kind`
+    fn describe(&self, kind: Kind) -> String {
+        let numbers = self.schematic.numbers();
+        let symbols = self.schematic.symbols();
+        match kind {
+            Kind::Empty => String::new(),
+            Kind::Part(i) => {
+                let number = &numbers[i];
+                let near = self
+                    .schematic
+                    .symbols_near(number)
+                    .map(|symbol| {
+                        format!(
+                            "{} at {}",
+                            symbol.character,
+                            position(symbol.row, symbol.column)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "{} is a part number, next to {near}",
+                    number.value
+                )
+            }
+            Kind::Number(i) => format!(
+                "{} isn't a part number, no symbol within {}",
+                numbers[i].value,
+                self.schematic.radius()
+            ),
+            Kind::Gear(i) => {
+                let gear = &self.gears[i];
+                format!(
+                    "gear at {}: {} × {} = {}",
+                    position(gear.symbol.row, gear.symbol.column),
+                    gear.numbers[0].value,
+                    gear.numbers[1].value,
+                    gear.ratio
+                )
+            }
+            Kind::Symbol(i) => {
+                let symbol = &symbols[i];
+                let near = self
+                    .schematic
+                    .numbers_near(symbol)
+                    .map(|number| number.value.to_string())
+                    .collect::<Vec<_>>();
+                format!(
+                    "{} at {}, next to {}",
+                    symbol.character,
+                    position(symbol.row, symbol.column),
+                    if near.is_empty() {
+                        "no numbers".to_string()
+                    } else {
+                        near.join(", ")
+                    }
+                )
+            }
+        }
+    }
+
+    /// A standalone HTML page showing the
+    /// schematic in the same colours, with a
+    /// tooltip on every number and symbol
+    pub fn to_html(&self) -> String {
+        let css = |Rgb(r, g, b): Rgb| {
+            format!("rgb({r}, {g}, {b})")
+        };
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Day 3 schematic</title>
+<style>
+body {{ background: {black}; color: {white}; }}
+pre {{ color: {dim}; }}
+.part {{ color: {green}; }}
+.number {{ color: {red}; }}
+.symbol {{ color: {yellow}; }}
+.gear {{ color: {black}; background: {yellow}; }}
+.ratio {{ color: {yellow}; }}
+</style>
+</head>
+<body>
+<pre>
+",
+            black = css(Rgb::BLACK),
+            white = css(Rgb::WHITE),
+            dim = css(Rgb::DIM),
+            green = css(Rgb::GREEN),
+            red = css(Rgb::RED),
+            yellow = css(Rgb::YELLOW),
+        );
+        for (y, (row, kinds)) in
+            self.rows.iter().zip(&self.kinds).enumerate()
+        {
+            let mut x = 0;
+            while x < row.len() {
+                let kind = kinds[x];
+                // a number's digits share one span
+                let end = x + kinds[x..]
+                    .iter()
+                    .take_while(|&&other| {
+                        other == kind
+                            && matches!(
+                                kind,
+                                Kind::Part(_)
+                                    | Kind::Number(_)
+                            )
+                    })
+                    .count()
+                    .max(1);
+                let text = escape(
+                    &row[x..end].iter().collect::<String>(),
+                );
+                let class = match kind {
+                    Kind::Empty => None,
+                    Kind::Part(_) => Some("part"),
+                    Kind::Number(_) => Some("number"),
+                    Kind::Gear(_) => Some("gear"),
+                    Kind::Symbol(_) => Some("symbol"),
+                };
+                match class {
+                    None => html.push_str(&text),
+                    Some(class) => {
+                        let _ = write!(
+                            html,
+                            "<span class=\"{class}\" title=\"{}\">{text}</span>",
+                            escape(&self.describe(kind))
+                        );
+                    }
+                }
+                x = end;
+            }
+            let ratios = self.row_ratios(y);
+            if !ratios.is_empty() {
+                let _ = write!(
+                    html,
+                    "<span class=\"ratio\">{}</span>",
+                    escape(&ratios)
+                );
+            }
+            html.push('\n');
+        }
+        let _ = write!(
+            html,
+            "</pre>\n<p>{}</p>\n</body>\n</html>\n",
+            self.totals()
+        );
+        html
+    }
+}
+
+/// Counting rows and columns from 1, as an
+/// editor would
+fn position(row: usize, column: usize) -> String {
+    format!("row {}, column {}", row + 1, column + 1)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part1, part2};
+
+    const INPUT: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn test_totals() -> miette::Result<()> {
+        let annotated =
+            Annotated::new(INPUT, &Params::default())?;
+        assert_eq!(
+            part1::process(INPUT)?,
+            annotated.part_number_sum()
+        );
+        assert_eq!(
+            part2::process(INPUT)?,
+            annotated.gear_ratio_sum()
+        );
+        assert_eq!(2, annotated.gears().len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_frame() -> Result<(), AocError> {
+        let frame =
+            Annotated::new(INPUT, &Params::default())?
+                .to_frame();
+        assert_eq!(
+            "467..114..
+...*......  467*35=16345
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....  755*598=451490
+.664.598..
+part number sum 4361, gear ratio sum 467835
+",
+            frame
+                .to_ascii()
+                .lines()
+                .map(|line| line.trim_end().to_string()
+                    + "\n")
+                .collect::<String>()
+        );
+        let colour = |x, y| frame.get(x, y).unwrap().fg;
+        assert_eq!(Rgb::GREEN, colour(0, 0));
+        assert_eq!(Rgb::RED, colour(5, 0));
+        assert_eq!(Rgb::YELLOW, colour(6, 3));
+        assert_eq!(Rgb::YELLOW, colour(12, 1));
+        assert_eq!(
+            Some(Rgb::YELLOW),
+            frame.get(3, 1).unwrap().bg
+        );
+        // the `*` next to 617 alone isn't a gear
+        assert_eq!(None, frame.get(3, 4).unwrap().bg);
+        Ok(())
+    }
+
+    #[test]
+    fn test_html() -> Result<(), AocError> {
+        let html =
+            Annotated::new(INPUT, &Params::default())?
+                .to_html();
+        assert!(html.contains(
+            "<span class=\"part\" title=\"467 is a part number, next to * at row 2, column 4\">467</span>"
+        ));
+        assert!(html.contains(
+            "<span class=\"number\" title=\"114 isn't a part number, no symbol within 1\">114</span>"
+        ));
+        assert!(html.contains(
+            "<span class=\"gear\" title=\"gear at row 2, column 4: 467 × 35 = 16345\">*</span>"
+        ));
+        assert!(html.contains(
+            "<span class=\"symbol\" title=\"* at row 5, column 4, next to 617\">*</span>"
+        ));
+        assert!(html.contains(
+            "<p>part number sum 4361, gear ratio sum 467835</p>"
+        ));
+
+        let html =
+            Annotated::new("1&2\n<..", &Params::default())?
+                .to_html();
+        assert!(html.contains(
+            "<span class=\"symbol\" title=\"&amp; at row 1, column 2, next to 1, 2\">&amp;</span>"
+        ));
+        assert!(html.contains(
+            "<span class=\"symbol\" title=\"&lt; at row 2, column 1, next to 1\">&lt;</span>"
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,387 @@
+//! The engine schematic as numbers and symbols
+//! with their exact positions, for asking which
+//! numbers are near which symbols.
+//!
+//! Rows and columns count from 0, and columns
+//! count characters rather than bytes. A number
+//! keeps the span of its digits, so `007` is
+//! three columns wide even though its value is 7.
+//! Anything that isn't a digit or `.` is a
+//! symbol.
+use std::{ops::Range, str::FromStr};
+
+use aoc_common::lines::input_lines;
+
+use crate::custom_error::AocError;
+
+/// The columns `start..end` of one row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub row: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// How many columns the span covers
+    pub fn width(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Number {
+    pub value: u32,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol {
+    pub row: usize,
+    pub column: usize,
+    pub character: char,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schematic {
+    /// In reading order
+    numbers: Vec<Number>,
+    /// In reading order
+    symbols: Vec<Symbol>,
+    /// The indices of each row's numbers
+    number_rows: Vec<Range<usize>>,
+    /// The indices of each row's symbols
+    symbol_rows: Vec<Range<usize>>,
+    radius: usize,
+}
+
+impl Schematic {
+    pub fn parse(input: &str) -> Result<Self, AocError> {
+        let mut numbers = vec![];
+        let mut symbols = vec![];
+        let mut number_rows = vec![];
+        let mut symbol_rows = vec![];
+        for (row, line) in input_lines(input).enumerate() {
+            let first_number = numbers.len();
+            let first_symbol = symbols.len();
+            let mut digits: Option<(usize, u32)> = None;
+            for (column, character) in
+                line.chars().chain(['.']).enumerate()
+            {
+                if let Some(digit) = character.to_digit(10)
+                {
+                    let (start, value) =
+                        digits.unwrap_or((column, 0));
+                    let value = value
+                        .checked_mul(10)
+                        .and_then(|value| {
+                            value.checked_add(digit)
+                        })
+                        .ok_or(
+                            AocError::NumberTooLarge {
+                                row,
+                                column: start,
+                            },
+                        )?;
+                    digits = Some((start, value));
+                    continue;
+                }
+                if let Some((start, value)) = digits.take()
+                {
+                    numbers.push(Number {
+                        value,
+                        span: Span {
+                            row,
+                            start,
+                            end: column,
+                        },
+                    });
+                }
+                if character != '.' {
+                    symbols.push(Symbol {
+                        row,
+                        column,
+                        character,
+                    });
+                }
+            }
+            number_rows.push(first_number..numbers.len());
+            symbol_rows.push(first_symbol..symbols.len());
+        }
+        Ok(Self {
+            numbers,
+            symbols,
+            number_rows,
+            symbol_rows,
+            radius: 1,
+        })
+    }
+
+    /// How far a symbol can be from a number, in
+    /// rows and columns, and still be adjacent to
+    /// it. Diagonals count, and the default is 1.
+    /// A radius past the edges of the schematic
+    /// reaches every symbol.
+    pub fn with_radius(mut self, radius: usize) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    pub fn is_adjacent(
+        &self,
+        number: &Number,
+        symbol: &Symbol,
+    ) -> bool {
+        let span = number.span;
+        symbol.row.abs_diff(span.row) <= self.radius
+            && symbol.column.saturating_add(self.radius)
+                >= span.start
+            && symbol.column
+                < span.end.saturating_add(self.radius)
+    }
+
+    /// The rows within the radius of `row`
+    fn rows_near(&self, row: usize) -> Range<usize> {
+        row.saturating_sub(self.radius)
+            ..row
+                .saturating_add(self.radius)
+                .saturating_add(1)
+                .min(self.number_rows.len())
+    }
+
+    /// The numbers adjacent to `symbol`, in
+    /// reading order
+    pub fn numbers_near<'a>(
+        &'a self,
+        symbol: &'a Symbol,
+    ) -> impl Iterator<Item = &'a Number> + 'a {
+        self.rows_near(symbol.row)
+            .flat_map(|row| {
+                &self.numbers[self.number_rows[row].clone()]
+            })
+            .filter(|number| {
+                self.is_adjacent(number, symbol)
+            })
+    }
+
+    /// The symbols adjacent to `number`, in
+    /// reading order
+    pub fn symbols_near<'a>(
+        &'a self,
+        number: &'a Number,
+    ) -> impl Iterator<Item = &'a Symbol> + 'a {
+        self.rows_near(number.span.row)
+            .flat_map(|row| {
+                &self.symbols[self.symbol_rows[row].clone()]
+            })
+            .filter(|symbol| {
+                self.is_adjacent(number, symbol)
+            })
+    }
+
+    /// The numbers adjacent to a symbol for which
+    /// `is_wanted` is true, each once
+    pub fn numbers_near_any<'a>(
+        &'a self,
+        is_wanted: impl Fn(char) -> bool + 'a,
+    ) -> impl Iterator<Item = &'a Number> + 'a {
+        self.numbers.iter().filter(move |number| {
+            self.symbols_near(number)
+                .any(|symbol| is_wanted(symbol.character))
+        })
+    }
+
+    /// The numbers adjacent to any symbol, which
+    /// are the puzzle's part numbers
+    pub fn part_numbers(
+        &self,
+    ) -> impl Iterator<Item = &Number> {
+        self.numbers_near_any(|_| true)
+    }
+
+    /// Each symbol adjacent to exactly `count`
+    /// numbers, with those numbers
+    pub fn symbols_near_exactly(
+        &self,
+        count: usize,
+    ) -> impl Iterator<Item = (&Symbol, Vec<&Number>)> {
+        self.symbols.iter().filter_map(move |symbol| {
+            let numbers = self
+                .numbers_near(symbol)
+                .collect::<Vec<_>>();
+            (numbers.len() == count)
+                .then_some((symbol, numbers))
+        })
+    }
+}
+
+impl FromStr for Schematic {
+    type Err = AocError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    fn values<'a>(
+        numbers: impl IntoIterator<Item = &'a Number>,
+    ) -> Vec<u32> {
+        numbers
+            .into_iter()
+            .map(|number| number.value)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() -> Result<(), AocError> {
+        let schematic: Schematic = INPUT.parse()?;
+        assert_eq!(10, schematic.numbers().len());
+        assert_eq!(6, schematic.symbols().len());
+        assert_eq!(
+            Number {
+                value: 114,
+                span: Span {
+                    row: 0,
+                    start: 5,
+                    end: 8
+                }
+            },
+            schematic.numbers()[1]
+        );
+        assert_eq!(
+            Symbol {
+                row: 3,
+                column: 6,
+                character: '#'
+            },
+            schematic.symbols()[1]
+        );
+
+        // leading zeros still take up columns
+        let schematic: Schematic = "007*\n..€1".parse()?;
+        let number = schematic.numbers()[0];
+        assert_eq!(7, number.value);
+        assert_eq!(3, number.span.width());
+        assert_eq!(
+            vec![('*', 3), ('€', 2)],
+            schematic
+                .symbols()
+                .iter()
+                .map(|symbol| (
+                    symbol.character,
+                    symbol.column
+                ))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(3, schematic.numbers()[1].span.start);
+
+        assert!(matches!(
+            Schematic::parse(".\n99999999999"),
+            Err(AocError::NumberTooLarge {
+                row: 1,
+                column: 0
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_queries() -> Result<(), AocError> {
+        let schematic = Schematic::parse(INPUT)?;
+        assert_eq!(
+            4361,
+            schematic
+                .part_numbers()
+                .map(|number| number.value)
+                .sum::<u32>()
+        );
+        assert_eq!(
+            vec![467, 35, 617, 755, 598],
+            values(
+                schematic.numbers_near_any(|c| c == '*')
+            )
+        );
+        assert_eq!(
+            vec![633],
+            values(
+                schematic.numbers_near_any(|c| c == '#')
+            )
+        );
+        let gears = schematic
+            .symbols_near_exactly(2)
+            .filter(|(symbol, _)| symbol.character == '*')
+            .map(|(_, numbers)| values(numbers))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![vec![467, 35], vec![755, 598]],
+            gears
+        );
+        assert_eq!(
+            vec!['*'],
+            schematic
+                .symbols_near_exactly(1)
+                .map(|(symbol, _)| symbol.character)
+                .filter(|&c| c == '*')
+                .collect::<Vec<_>>()
+        );
+        let number = schematic.numbers()[0];
+        assert_eq!(
+            vec!['*'],
+            schematic
+                .symbols_near(&number)
+                .map(|symbol| symbol.character)
+                .collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_radius() -> Result<(), AocError> {
+        let input = "1....\n..*..\n....2\n.....\n3....";
+        let schematic = Schematic::parse(input)?;
+        assert_eq!(0, schematic.part_numbers().count());
+        let schematic = schematic.with_radius(2);
+        assert_eq!(
+            vec![1, 2],
+            values(schematic.part_numbers())
+        );
+        let schematic = schematic.with_radius(3);
+        assert_eq!(
+            vec![1, 2, 3],
+            values(schematic.part_numbers())
+        );
+        let schematic = schematic.with_radius(usize::MAX);
+        assert_eq!(
+            vec![1, 2, 3],
+            values(schematic.part_numbers())
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+//! The schematic scanned a row at a time, part
+//! numbers adjacent to a symbol turning green and
+//! the rest red, with the running total.
+use std::time::Duration;
+
+use aoc_common::{
+    lines::input_lines,
+    visualize::{Frame, Rgb, Visualize},
+};
+
+use crate::{custom_error::AocError, schematic::Schematic};
+
+pub struct Highlight {
+    text: String,
+    schematic: Schematic,
+}
+
+impl Highlight {
+    pub fn new(input: &str) -> Result<Self, AocError> {
+        Ok(Self {
+            text: input_lines(input)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            schematic: Schematic::parse(input)?,
+        })
+    }
+}
+
+impl Visualize for Highlight {
+    fn frames(&self) -> Vec<Frame> {
+        let mut frame = Frame::from_grid(&self.text);
+        for symbol in self.schematic.symbols() {
+            frame.colour(
+                symbol.column,
+                symbol.row,
+                Rgb::YELLOW,
+            );
+        }
+
+        let numbers = self.schematic.numbers();
+        let mut frames = vec![];
+        let mut sum = 0;
+        let mut parts = 0;
+        for y in 0..self.text.lines().count() {
+            for number in
+                numbers.iter().filter(|n| n.span.row == y)
+            {
+                let is_part = self
+                    .schematic
+                    .symbols_near(number)
+                    .next()
+                    .is_some();
+                let colour = if is_part {
+                    Rgb::GREEN
+                } else {
+                    Rgb::RED
+                };
+                for x in number.span.start..number.span.end
+                {
+                    frame.mark(x, y, colour);
+                }
+                if is_part {
+                    sum += number.value;
+                    parts += 1;
+                }
+            }
+            let mut row_frame = frame.clone();
+            row_frame.caption(format!(
+                "row {}: {parts} part numbers, sum {sum}",
+                y + 1
+            ));
+            frames
+                .push(row_frame.with_delay(
+                    Duration::from_millis(150),
+                ));
+        }
+        if let Some(last) = frames.last_mut() {
+            last.delay = Duration::from_secs(2);
+        }
+        frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn test_frames() {
+        let frames =
+            Highlight::new(TEST_INPUT).unwrap().frames();
+        assert_eq!(10, frames.len());
+        let last = &frames[9];
+        assert!(last.to_ascii().ends_with(
+            "row 10: 8 part numbers, sum 4361\n"
+        ));
+        // 467 is a part number, 114 isn't
+        assert_eq!(
+            Some(Rgb::GREEN),
+            last.get(0, 0).unwrap().bg
+        );
+        assert_eq!(
+            Some(Rgb::RED),
+            last.get(5, 0).unwrap().bg
+        );
+        assert_eq!(Rgb::YELLOW, last.get(3, 1).unwrap().fg);
+        // rows below the scan aren't marked yet
+        assert_eq!(None, frames[0].get(2, 2).unwrap().bg);
+    }
+}
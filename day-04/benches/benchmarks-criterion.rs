@@ -4,23 +4,35 @@ use criterion::{
 use day_04::*;
 
 fn criterion_benchmark_part1(c: &mut Criterion) {
-    let input = include_str!("../input1.txt");
+    let input =
+        aoc_common::input::load_day(env!("CARGO_PKG_NAME"))
+            .unwrap();
 
     let mut group = c.benchmark_group("day_04::part1");
-    group.bench_with_input("part1", input, |b, input| {
-        b.iter(|| part1::process(input))
-    });
+    for variant in part1::VARIANTS {
+        group.bench_with_input(
+            variant.name,
+            input.as_str(),
+            |b, input| b.iter(|| (variant.process)(input)),
+        );
+    }
 
     group.finish();
 }
 
 fn criterion_benchmark_part2(c: &mut Criterion) {
-    let input = include_str!("../input2.txt");
+    let input =
+        aoc_common::input::load_day(env!("CARGO_PKG_NAME"))
+            .unwrap();
 
     let mut group = c.benchmark_group("day_04::part2");
-    group.bench_with_input("part2", input, |b, input| {
-        b.iter(|| part2::process(input))
-    });
+    for variant in part2::VARIANTS {
+        group.bench_with_input(
+            variant.name,
+            input.as_str(),
+            |b, input| b.iter(|| (variant.process)(input)),
+        );
+    }
 
     group.finish();
 }
@@ -1,22 +1,33 @@
-use day_04::*;
+use aoc_common::variants::Variant;
+use day_04::{custom_error::AocError, *};
 
 fn main() {
     // Run registered benchmarks.
     divan::main();
 }
 
-#[divan::bench]
-fn part1() {
-    part1::process(divan::black_box(include_str!(
-        "../input1.txt",
-    )))
-    .unwrap();
+#[divan::bench(args = part1::VARIANTS)]
+fn part1(
+    bencher: divan::Bencher,
+    variant: &Variant<i64, AocError>,
+) {
+    let input =
+        aoc_common::input::load_day(env!("CARGO_PKG_NAME"))
+            .unwrap();
+    bencher.bench(|| {
+        (variant.process)(divan::black_box(&input)).unwrap()
+    });
 }
 
-#[divan::bench]
-fn part2() {
-    part2::process(divan::black_box(include_str!(
-        "../input2.txt",
-    )))
-    .unwrap();
-}
\ No newline at end of file
+#[divan::bench(args = part2::VARIANTS)]
+fn part2(
+    bencher: divan::Bencher,
+    variant: &Variant<u64, AocError>,
+) {
+    let input =
+        aoc_common::input::load_day(env!("CARGO_PKG_NAME"))
+            .unwrap();
+    bencher.bench(|| {
+        (variant.process)(divan::black_box(&input)).unwrap()
+    });
+}
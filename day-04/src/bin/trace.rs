@@ -0,0 +1,86 @@
+//! Count part 2's cards in `u128` and optionally
+//! write where each card's copies came from as
+//! CSV.
+//!
+//! ```sh
+//! trace [--csv <path>] [--synthetic <cards> [--pattern 0,1,2]]
+//! ```
+//!
+//! `--synthetic` replaces the input with that
+//! many cards whose matches repeat `--pattern`,
+//! and `--csv -` writes the trace to stdout.
+use day_04::{
+    card::{match_counts, parse_cards},
+    cascade::{
+        synthetic_input, to_csv, total_cards, trace,
+    },
+};
+use miette::{miette, IntoDiagnostic};
+
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut csv = None;
+    let mut synthetic = None;
+    let mut pattern = vec![0, 1, 2];
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next().ok_or_else(|| {
+                miette!("`{arg}` needs a value")
+            })
+        };
+        match arg.as_str() {
+            "--csv" => csv = Some(value()?),
+            "--synthetic" => {
+                synthetic =
+                    Some(value()?.parse::<usize>().map_err(
+                        |_| miette!("`--synthetic` must be a number of cards"),
+                    )?)
+            }
+            "--pattern" => {
+                pattern = value()?
+                    .split(',')
+                    .map(|n| n.trim().parse::<usize>())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| {
+                        miette!("`--pattern` must be matches separated by commas, such as 0,1,2")
+                    })?
+            }
+            _ => {
+                return Err(miette!(
+                    "unexpected argument `{arg}`"
+                ))
+            }
+        }
+    }
+
+    let input = match synthetic {
+        Some(cards) => synthetic_input(
+            &pattern
+                .iter()
+                .copied()
+                .cycle()
+                .take(cards)
+                .collect::<Vec<_>>(),
+        )?,
+        None => aoc_common::input::load_day(env!(
+            "CARGO_PKG_NAME"
+        ))?,
+    };
+    let cards = parse_cards(&input)?;
+    let matches = match_counts(&cards);
+    let trace = trace::<u128>(&matches)?;
+    let total = total_cards::<u128>(&matches)?;
+    eprintln!("{} cards, {total} in all", trace.len());
+    match csv.as_deref() {
+        Some("-") => print!("{}", to_csv(&trace)),
+        Some(path) => {
+            std::fs::write(path, to_csv(&trace))
+                .into_diagnostic()?;
+            eprintln!("wrote the trace to `{path}`");
+        }
+        None => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,324 @@
+//! A scratchcard such as `Card 3: 1 21 53 | 69 82
+//! 1`, parsed once into a [`Scratchcard`] and
+//! shared by both parts.
+//!
+//! Every number is below 128, so each side of the
+//! card fits in a [`NumberSet`] of one bit per
+//! number and the matches are a single `&` and
+//! popcount. A number listed twice on one side
+//! counts once.
+use std::{collections::HashSet, ops::BitAnd};
+
+use aoc_common::{
+    lines::input_lines,
+    parse::{integer, lines, parse_all, PResult},
+};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, digit1, space0, space1},
+    combinator::{cut, map, peek, verify},
+    error::context,
+    multi::fold_many0,
+    sequence::{
+        pair, preceded, separated_pair, terminated,
+    },
+};
+use rayon::prelude::*;
+
+use crate::custom_error::AocError;
+
+/// A set of numbers below [`NumberSet::LIMIT`],
+/// one bit each
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NumberSet(u128);
+
+impl NumberSet {
+    pub const LIMIT: u8 = 128;
+
+    /// Panics if `number` isn't below
+    /// [`NumberSet::LIMIT`]
+    pub fn insert(&mut self, number: u8) {
+        assert!(
+            number < Self::LIMIT,
+            "{number} is too large"
+        );
+        self.0 |= 1 << number;
+    }
+
+    pub fn contains(&self, number: u8) -> bool {
+        number < Self::LIMIT && self.0 & (1 << number) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitAnd for NumberSet {
+    type Output = NumberSet;
+
+    fn bitand(self, other: Self) -> Self::Output {
+        NumberSet(self.0 & other.0)
+    }
+}
+
+impl FromIterator<u8> for NumberSet {
+    fn from_iter<I: IntoIterator<Item = u8>>(
+        numbers: I,
+    ) -> Self {
+        let mut set = NumberSet::default();
+        for number in numbers {
+            set.insert(number);
+        }
+        set
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scratchcard {
+    pub id: u32,
+    pub winning: NumberSet,
+    pub have: NumberSet,
+}
+
+impl Scratchcard {
+    /// Parse a single line
+    pub fn parse(line: &str) -> Result<Self, AocError> {
+        Ok(parse_all(line, card)?)
+    }
+
+    /// How many of the numbers you have are
+    /// winning numbers
+    pub fn matches(&self) -> usize {
+        (self.winning & self.have).len()
+    }
+}
+
+fn number(input: &str) -> PResult<'_, u8> {
+    // once there is a digit it must be a number
+    // that fits
+    preceded(
+        peek(digit1),
+        cut(map(
+            context(
+                "a number below 128",
+                verify(integer::<u32>, |&number| {
+                    number < u32::from(NumberSet::LIMIT)
+                }),
+            ),
+            |number| number as u8,
+        )),
+    )(input)
+}
+
+/// Numbers separated by spaces, possibly none
+fn number_set(input: &str) -> PResult<'_, NumberSet> {
+    fold_many0(
+        terminated(number, space0),
+        NumberSet::default,
+        |mut set, number| {
+            set.insert(number);
+            set
+        },
+    )(input)
+}
+
+/// `Card <id>: ` then the winning numbers, `|`
+/// and the numbers you have. Either side may be
+/// empty.
+pub fn card(input: &str) -> PResult<'_, Scratchcard> {
+    context(
+        "a card",
+        map(
+            pair(
+                preceded(
+                    pair(tag("Card"), space1),
+                    integer,
+                ),
+                preceded(
+                    pair(char(':'), space0),
+                    cut(separated_pair(
+                        number_set,
+                        pair(char('|'), space0),
+                        number_set,
+                    )),
+                ),
+            ),
+            |(id, (winning, have))| Scratchcard {
+                id,
+                winning,
+                have,
+            },
+        ),
+    )(input)
+}
+
+/// Check that `card` is numbered `expected`,
+/// one after the card before it
+pub fn check_id(
+    card: &Scratchcard,
+    expected: u32,
+) -> Result<(), AocError> {
+    if card.id == expected {
+        Ok(())
+    } else {
+        Err(AocError::OutOfOrder {
+            expected,
+            found: card.id,
+        })
+    }
+}
+
+/// Every card in the input, one per line,
+/// numbered from 1 with none missing
+pub fn parse_cards(
+    input: &str,
+) -> Result<Vec<Scratchcard>, AocError> {
+    let cards = parse_all(input, lines(card))?;
+    for (card, expected) in cards.iter().zip(1..) {
+        check_id(card, expected)?;
+    }
+    Ok(cards)
+}
+
+/// Each card's matches, worked out in parallel
+pub fn match_counts(cards: &[Scratchcard]) -> Vec<usize> {
+    cards.par_iter().map(Scratchcard::matches).collect()
+}
+
+/// The matches on one line, counted the way the
+/// original solution did and kept as the baseline
+/// for the benchmarks. It collects the winning
+/// numbers into a [`HashSet`] and counts each
+/// number you have that's in it, skipping
+/// anything that isn't a number or `|`, so it
+/// doesn't check the card ids or limit the
+/// numbers' size. A number you have listed twice
+/// counts twice, where a [`NumberSet`] counts it
+/// once, but the puzzle never repeats a number on
+/// a card.
+pub fn matches_with_hash_set(line: &str) -> usize {
+    let parts = line.split_whitespace();
+    let mut winning_nums: HashSet<i32> = HashSet::new();
+    let mut matched_numbers = 0;
+    let mut seen_all_winning_numbers = false;
+
+    for part in parts {
+        if let Ok(digit) = part.parse::<i32>() {
+            if seen_all_winning_numbers {
+                if winning_nums.contains(&digit) {
+                    matched_numbers += 1;
+                }
+            } else {
+                winning_nums.insert(digit);
+            }
+        } else if part == "|" {
+            seen_all_winning_numbers = true;
+        }
+    }
+    matched_numbers
+}
+
+/// [`matches_with_hash_set`] for every line
+pub fn match_counts_with_hash_set(
+    input: &str,
+) -> Vec<usize> {
+    input_lines(input).map(matches_with_hash_set).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str =
+        "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    #[test]
+    fn test_number_set() {
+        let set: NumberSet =
+            [0, 5, 127, 5].into_iter().collect();
+        assert_eq!(3, set.len());
+        assert!(set.contains(127));
+        assert!(!set.contains(1));
+        assert!(!set.contains(200));
+        let other: NumberSet = [5, 6].into_iter().collect();
+        assert_eq!(1, (set & other).len());
+        assert!(NumberSet::default().is_empty());
+    }
+
+    #[test]
+    fn test_parse() -> Result<(), AocError> {
+        let card = Scratchcard::parse(
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+        )?;
+        assert_eq!(3, card.id);
+        assert_eq!(5, card.winning.len());
+        assert!(card.have.contains(82));
+        assert_eq!(2, card.matches());
+
+        let card = Scratchcard::parse("Card 9: | 1 2")?;
+        assert!(card.winning.is_empty());
+        assert_eq!(0, card.matches());
+
+        let cards = parse_cards(INPUT)?;
+        assert_eq!(
+            vec![4, 2, 2, 1, 0, 0],
+            match_counts(&cards)
+        );
+        assert_eq!(
+            match_counts(&cards),
+            match_counts_with_hash_set(INPUT)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_repeated_numbers() -> miette::Result<()> {
+        let line = "Card 1: 5 6 5 | 5 5 7 6";
+        assert_eq!(2, Scratchcard::parse(line)?.matches());
+        assert_eq!(3, matches_with_hash_set(line));
+        Ok(())
+    }
+
+    #[test]
+    fn test_number_too_large() {
+        let line = "Card 1: 41 128 | 83";
+        let Err(AocError::Parse(error)) =
+            Scratchcard::parse(line)
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!("a number below 128", error.expected);
+        assert_eq!(
+            line.find("128").unwrap(),
+            error.offset()
+        );
+    }
+
+    #[test]
+    fn test_ids() {
+        assert!(matches!(
+            parse_cards("Card 1: 1 | 1\nCard 3: 2 | 2"),
+            Err(AocError::OutOfOrder {
+                expected: 2,
+                found: 3
+            })
+        ));
+        assert!(matches!(
+            parse_cards("Card 2: 1 | 1"),
+            Err(AocError::OutOfOrder {
+                expected: 1,
+                found: 2
+            })
+        ));
+    }
+}
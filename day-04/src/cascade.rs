@@ -0,0 +1,376 @@
+//! How many copies of each card part 2 ends up
+//! with, and which earlier cards they were won
+//! from.
+//!
+//! Copies can double with every card, so the
+//! counts are generic over [`Count`] and every
+//! addition is checked whether or not the
+//! `checked-math` feature is on. Part 2 counts in
+//! `u64`, and `u128` goes further for synthetic
+//! inputs. A card's matches never win copies of
+//! cards past the end of the table.
+use std::fmt::{Debug, Display, Write};
+
+use aoc_common::checked::Overflow;
+
+use crate::custom_error::AocError;
+
+/// An unsigned integer type to count copies in
+pub trait Count:
+    Copy + Debug + Display + PartialEq
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! count {
+    ($($ty:ty),+) => {
+        $(impl Count for $ty {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$ty>::checked_add(self, other)
+            }
+        })+
+    };
+}
+
+count!(u32, u64, u128);
+
+/// `a + b`, or an [`Overflow`] naming the
+/// `operation` and saying what was being counted
+pub(crate) fn add<T: Count>(
+    a: T,
+    b: T,
+    operation: &'static str,
+    context: impl FnOnce() -> String,
+) -> Result<T, Overflow> {
+    a.checked_add(b).ok_or_else(|| Overflow {
+        operation,
+        context: context(),
+    })
+}
+
+/// How many copies of each card there are once
+/// every card has been scratched, given each
+/// card's matches
+pub fn copies<T: Count>(
+    matches: &[usize],
+) -> Result<Vec<T>, AocError> {
+    let mut copies = vec![T::ONE; matches.len()];
+    for (i, &won) in matches.iter().enumerate() {
+        let these = copies[i];
+        for (j, copies) in copies
+            .iter_mut()
+            .enumerate()
+            .skip(i + 1)
+            .take(won)
+        {
+            *copies = add(
+                *copies,
+                these,
+                "copies + won",
+                || {
+                    format!(
+                        "card {} won copies of card {}",
+                        i + 1,
+                        j + 1
+                    )
+                },
+            )?;
+        }
+    }
+    Ok(copies)
+}
+
+/// Part 2's answer, the number of cards once
+/// every card has been scratched
+pub fn total_cards<T: Count>(
+    matches: &[usize],
+) -> Result<T, AocError> {
+    let cards = matches.len();
+    copies::<T>(matches)?.into_iter().try_fold(
+        T::ZERO,
+        |total, copies| {
+            Ok(add(
+                total,
+                copies,
+                "total + copies",
+                || format!("adding up {cards} cards"),
+            )?)
+        },
+    )
+}
+
+/// Where one card's copies came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardTrace<T> {
+    /// Counting from 1
+    pub card: usize,
+    pub matches: usize,
+    /// The original and every copy won
+    pub copies: T,
+    /// Each earlier card that won copies of this
+    /// one, with how many copies it won
+    pub won_from: Vec<(usize, T)>,
+}
+
+/// [`copies`] with where each card's copies came
+/// from
+pub fn trace<T: Count>(
+    matches: &[usize],
+) -> Result<Vec<CardTrace<T>>, AocError> {
+    let mut cards = matches
+        .iter()
+        .enumerate()
+        .map(|(i, &matches)| CardTrace {
+            card: i + 1,
+            matches,
+            copies: T::ONE,
+            won_from: vec![],
+        })
+        .collect::<Vec<_>>();
+    for i in 0..cards.len() {
+        let (card, these) =
+            (cards[i].card, cards[i].copies);
+        for later in
+            cards.iter_mut().skip(i + 1).take(matches[i])
+        {
+            later.copies = add(
+                later.copies,
+                these,
+                "copies + won",
+                || {
+                    format!(
+                        "card {card} won copies of card {}",
+                        later.card
+                    )
+                },
+            )?;
+            later.won_from.push((card, these));
+        }
+    }
+    Ok(cards)
+}
+
+/// One row per card, with the cards it was won
+/// from as `card:copies` separated by `;`
+pub fn to_csv<T: Count>(trace: &[CardTrace<T>]) -> String {
+    let mut csv =
+        String::from("card,matches,copies,won_from\n");
+    for card in trace {
+        let won_from = card
+            .won_from
+            .iter()
+            .map(|(from, copies)| {
+                format!("{from}:{copies}")
+            })
+            .collect::<Vec<_>>()
+            .join(";");
+        let _ = writeln!(
+            csv,
+            "{},{},{},{won_from}",
+            card.card, card.matches, card.copies
+        );
+    }
+    csv
+}
+
+/// An input with cards having the given matches,
+/// cut short so no card wins copies past the end
+/// as the puzzle promises. Each side of a card
+/// lists `0` up to its matches, so a card can't
+/// have more than 128.
+pub fn synthetic_input(
+    matches: &[usize],
+) -> Result<String, AocError> {
+    let mut input = String::new();
+    for (i, &won) in matches.iter().enumerate() {
+        let won = won.min(matches.len() - i - 1);
+        if won > 128 {
+            return Err(AocError::TooManyMatches {
+                card: i + 1,
+                matches: won,
+            });
+        }
+        let numbers = (0..won)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let line = format!(
+            "Card {}: {numbers} | {numbers}",
+            i + 1
+        );
+        let _ = writeln!(input, "{}", line.trim_end());
+    }
+    Ok(input)
+}
+
+/// Matches for every card winning a copy of each
+/// card after it, so `cards` cards make
+/// `2^cards - 1` in all
+#[cfg(test)]
+pub(crate) fn doubling(cards: usize) -> Vec<usize> {
+    (0..cards).rev().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        card::{match_counts, parse_cards},
+        part2,
+    };
+
+    const MATCHES: [usize; 6] = [4, 2, 2, 1, 0, 0];
+
+    #[test]
+    fn test_copies() -> Result<(), AocError> {
+        assert_eq!(
+            vec![1, 2, 4, 8, 14, 1],
+            copies::<u32>(&MATCHES)?
+        );
+        assert_eq!(30, total_cards::<u64>(&MATCHES)?);
+        assert_eq!(0, total_cards::<u64>(&[])?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_overflow() -> Result<(), AocError> {
+        assert_eq!(u64::MAX, total_cards(&doubling(64))?);
+        assert!(matches!(
+            total_cards::<u64>(&doubling(65)),
+            Err(AocError::Overflow(_))
+        ));
+        assert_eq!(
+            (1 << 65) - 1,
+            total_cards::<u128>(&doubling(65))?
+        );
+        assert_eq!(u128::MAX, total_cards(&doubling(128))?);
+        let Err(AocError::Overflow(overflow)) =
+            total_cards::<u128>(&doubling(129))
+        else {
+            panic!("expected an overflow");
+        };
+        assert_eq!("copies + won", overflow.operation);
+        assert_eq!(
+            "card 128 won copies of card 129",
+            overflow.context
+        );
+
+        // every count fits but their sum doesn't
+        let mut matches = doubling(32);
+        matches.push(0);
+        let Err(AocError::Overflow(overflow)) =
+            total_cards::<u32>(&matches)
+        else {
+            panic!("expected an overflow");
+        };
+        assert_eq!("total + copies", overflow.operation);
+        assert_eq!("adding up 33 cards", overflow.context);
+        Ok(())
+    }
+
+    #[test]
+    fn test_trace() -> Result<(), AocError> {
+        let trace = trace::<u64>(&MATCHES)?;
+        assert_eq!(
+            CardTrace {
+                card: 4,
+                matches: 1,
+                copies: 8,
+                won_from: vec![(1, 1), (2, 2), (3, 4)]
+            },
+            trace[3]
+        );
+        assert_eq!(
+            copies::<u64>(&MATCHES)?,
+            trace
+                .iter()
+                .map(|card| card.copies)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "card,matches,copies,won_from
+1,4,1,
+2,2,2,1:1
+3,2,4,1:1;2:2
+4,1,8,1:1;2:2;3:4
+5,0,14,1:1;3:4;4:8
+6,0,1,
+",
+            to_csv(&trace)
+        );
+        assert!(matches!(
+            super::trace::<u64>(&doubling(65)),
+            Err(AocError::Overflow(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_synthetic_input() -> miette::Result<()> {
+        let input = synthetic_input(&[3, 0, 1, 5])?;
+        assert_eq!(
+            "Card 1: 0 1 2 | 0 1 2
+Card 2:  |
+Card 3: 0 | 0
+Card 4:  |
+",
+            input
+        );
+        assert_eq!(
+            total_cards::<u64>(&[3, 0, 1, 0])?,
+            part2::process(&input)?
+        );
+
+        let input = synthetic_input(&[128; 130])?;
+        assert_eq!(
+            128,
+            match_counts(&parse_cards(&input)?)[0]
+        );
+        assert!(matches!(
+            synthetic_input(&[0, 129, 200].repeat(100)),
+            Err(AocError::TooManyMatches {
+                card: 2,
+                matches: 129
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_many_cards() -> miette::Result<()> {
+        // each card is won by exactly one card
+        // before it, so the copies only grow
+        // linearly: 2k + 1, 2k + 1 and 2k + 2 for
+        // the cards 3k + 1 to 3k + 3
+        let matches = [0, 1, 2].repeat(100_000);
+        let k = 100_000_u64;
+        let expected = 3 * k * k + k;
+        assert_eq!(expected, total_cards::<u64>(&matches)?);
+        let input = synthetic_input(&matches)?;
+        assert_eq!(expected, part2::process(&input)?);
+        assert_eq!(
+            expected,
+            part2::process_reader(input.as_bytes())?
+        );
+
+        // 2 matches each grows like the Fibonacci
+        // numbers, past u64 but not u128 by card 100
+        let mut matches = vec![2; 100];
+        matches.resize(300_000, 0);
+        assert!(matches!(
+            total_cards::<u64>(&matches),
+            Err(AocError::Overflow(_))
+        ));
+        assert!(
+            total_cards::<u128>(&matches)?
+                > u128::from(u64::MAX)
+        );
+        Ok(())
+    }
+}
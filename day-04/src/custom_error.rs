@@ -6,4 +6,30 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Overflow(#[from] aoc_common::checked::Overflow),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Parse(#[from] aoc_common::parse::ParseError),
+
+    #[error(
+        "expected card {expected}, found card {found}"
+    )]
+    #[diagnostic(
+        code(aoc::card_out_of_order),
+        help(
+            "cards are numbered from 1 with none missing"
+        )
+    )]
+    OutOfOrder { expected: u32, found: u32 },
+
+    #[error("card {card} can't have {matches} matches")]
+    #[diagnostic(
+        code(aoc::too_many_matches),
+        help("every number on a card is below 128, so a card has at most 128 matches")
+    )]
+    TooManyMatches { card: usize, matches: usize },
 }
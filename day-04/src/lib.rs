@@ -1,3 +1,5 @@
+pub mod card;
+pub mod cascade;
 pub mod custom_error;
 
 pub mod part1;
@@ -1,61 +1,104 @@
-use crate::custom_error::AocError;
-use std::collections::HashSet;
-use std::str;
+use crate::{
+    card::{
+        check_id, match_counts, match_counts_with_hash_set,
+        parse_cards, Scratchcard,
+    },
+    custom_error::AocError,
+};
+use aoc_common::{
+    checked, lines::try_for_each_line, variants::Variant,
+};
+use std::io::BufRead;
+
+pub const VARIANTS: &[Variant<i64, AocError>] = &[
+    Variant {
+        name: "bitset",
+        process,
+    },
+    Variant {
+        name: "hash set",
+        process: process_hash_set,
+    },
+];
 
 #[tracing::instrument]
 pub fn process(
     input: &str,
 ) -> miette::Result<i64, AocError> {
-    let lines = input.lines().map(|x| x.as_bytes());
-    // create iterator of lines split by whitespace
-    let mut score = 0;
-    let mut matches = Vec::new();
-    for line_result in lines {
-        let line = line_result;
-        let game_matches = calculate_game_nr_matches(line);
-        let game_score = get_score_from_number_of_matches(
-            game_matches as u32,
-        );
-        score += game_score;
-        matches.push(game_matches);
-    }
-    Ok(score as i64)
+    let cards = parse_cards(input)?;
+    total_score(match_counts(&cards))
 }
-fn calculate_game_nr_matches(line: &[u8]) -> i32 {
-    let parts =
-        str::from_utf8(line).unwrap().split_whitespace();
-    let mut winning_nums: HashSet<i32> = HashSet::new();
-    let mut matched_numbers = 0;
-    let mut seen_all_winning_numbers = false;
 
-    for part in parts {
-        if let Ok(digit) = part.parse::<i32>() {
-            if seen_all_winning_numbers {
-                if winning_nums.contains(&digit) {
-                    matched_numbers += 1;
-                }
-            } else {
-                winning_nums.insert(digit);
-            }
-        } else if part == "|" {
-            seen_all_winning_numbers = true;
-        }
-    }
-    matched_numbers
+/// Same as [`process`], counting each line's
+/// matches with the original solution,
+/// [`matches_with_hash_set`](crate::card::matches_with_hash_set)
+pub fn process_hash_set(
+    input: &str,
+) -> Result<i64, AocError> {
+    total_score(match_counts_with_hash_set(input))
+}
+
+/// Same as [`process`], reading one line at a
+/// time
+pub fn process_reader(
+    reader: impl BufRead,
+) -> miette::Result<i64, AocError> {
+    let mut score = 0_i64;
+    let mut id = 0;
+    try_for_each_line(reader, |line| {
+        let card = Scratchcard::parse(line)?;
+        id += 1;
+        check_id(&card, id)?;
+        let card_score = get_score_from_number_of_matches(
+            card.matches(),
+        )?;
+        score = checked!(
+            add(score, card_score),
+            "adding up the score of card {id}"
+        )?;
+        Ok::<_, AocError>(())
+    })?;
+    Ok(score)
+}
+
+fn total_score(
+    match_counts: Vec<usize>,
+) -> Result<i64, AocError> {
+    match_counts.into_iter().try_fold(
+        0_i64,
+        |score, matches| {
+            let card_score =
+                get_score_from_number_of_matches(matches)?;
+            Ok(checked!(
+                add(score, card_score),
+                "adding up the scores of the cards"
+            )?)
+        },
+    )
 }
 
 fn get_score_from_number_of_matches(
-    matched_numbers: u32,
-) -> i32 {
+    matched_numbers: usize,
+) -> Result<i64, AocError> {
     if matched_numbers == 0 {
-        return 0;
+        return Ok(0);
     }
-    2_i32.pow(matched_numbers - 1)
+    Ok(checked!(
+        pow(2_i64, matched_numbers as u32 - 1),
+        "scoring a card with {matched_numbers} matches"
+    )?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{
+        lines::assert_all_formats,
+        variants::{
+            assert_variants, assert_variants_agree_on_input,
+        },
+    };
+    use itertools::Itertools;
 
     #[test]
     fn test_process() -> miette::Result<()> {
@@ -65,7 +108,93 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        assert_eq!(13, process(input)?);
+        assert_variants(VARIANTS, input, 13);
+        assert_eq!(13, process_reader(input.as_bytes())?);
+        assert_all_formats(
+            |input| process_reader(input.as_bytes()),
+            input,
+            13,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input =
+            "Card 1: 41 48 | 83\nCard 2: 13 32 / 61";
+        let Err(AocError::Parse(error)) = process(input)
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!("`|`", error.expected);
+        assert_eq!(
+            input.find('/').unwrap(),
+            error.offset()
+        );
+        assert!(matches!(
+            process_reader(
+                "Card 1: 1 | 1\nCard 1: 2 | 2".as_bytes()
+            ),
+            Err(AocError::OutOfOrder {
+                expected: 2,
+                found: 1
+            })
+        ));
+    }
+
+    /// A card whose numbers are all in `numbers`
+    /// on both sides, matching every one
+    fn card(
+        id: u32,
+        numbers: std::ops::Range<u32>,
+    ) -> String {
+        let numbers =
+            numbers.map(|n| n.to_string()).join(" ");
+        format!("Card {id}: {numbers} | {numbers}")
+    }
+
+    #[test]
+    fn test_score_near_limit() -> miette::Result<()> {
+        let input = card(1, 0..63);
+        assert_variants(VARIANTS, &input, 1 << 62);
+        assert_eq!(
+            1 << 62,
+            process_reader(input.as_bytes())?
+        );
         Ok(())
     }
+
+    #[cfg(feature = "checked-math")]
+    #[test]
+    fn test_score_overflow() {
+        let too_many_matches = card(1, 0..64);
+        let too_many_cards = format!(
+            "{}\n{}",
+            card(1, 0..63),
+            card(2, 0..63)
+        );
+        for input in [&too_many_matches, &too_many_cards] {
+            for variant in VARIANTS {
+                assert!(
+                    matches!(
+                        (variant.process)(input),
+                        Err(AocError::Overflow(_))
+                    ),
+                    "{variant}"
+                );
+            }
+            assert!(matches!(
+                process_reader(input.as_bytes()),
+                Err(AocError::Overflow(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_variants_agree_on_input() {
+        assert_variants_agree_on_input(
+            VARIANTS,
+            env!("CARGO_PKG_NAME"),
+        );
+    }
 }
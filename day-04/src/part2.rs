@@ -1,66 +1,109 @@
-use crate::custom_error::AocError;
-use std::collections::HashSet;
-use std::str;
+use crate::{
+    card::{
+        check_id, match_counts, match_counts_with_hash_set,
+        parse_cards, Scratchcard,
+    },
+    cascade::{add, total_cards},
+    custom_error::AocError,
+};
+use aoc_common::{
+    lines::try_for_each_line, variants::Variant,
+};
+use std::collections::VecDeque;
+use std::io::BufRead;
+use tracing::debug;
+
+pub const VARIANTS: &[Variant<u64, AocError>] = &[
+    Variant {
+        name: "bitset",
+        process,
+    },
+    Variant {
+        name: "hash set",
+        process: process_hash_set,
+    },
+];
 
 #[tracing::instrument]
 pub fn process(
     input: &str,
-) -> miette::Result<i64, AocError> {
-    let lines = input.lines().map(|x| x.as_bytes());
-    // create iterator of lines split by whitespace
-    let mut matches = Vec::new();
-    for line_result in lines {
-        let line = line_result;
-        let game_matches = calculate_game_nr_matches(line);
-        matches.push(game_matches);
-    }
-    let part2_score = calculate_total_num_cards(matches);
-
-    println!("Total number of cards: {}", part2_score);
-    Ok(part2_score as i64)
+) -> miette::Result<u64, AocError> {
+    let cards = parse_cards(input)?;
+    let total = total_cards(&match_counts(&cards))?;
+    debug!(total, "total number of cards");
+    Ok(total)
 }
-fn calculate_game_nr_matches(line: &[u8]) -> i32 {
-    let parts =
-        str::from_utf8(line).unwrap().split_whitespace();
-    let mut winning_nums: HashSet<i32> = HashSet::new();
-    let mut matched_numbers = 0;
-    let mut seen_all_winning_numbers = false;
 
-    for part in parts {
-        if let Ok(digit) = part.parse::<i32>() {
-            if seen_all_winning_numbers {
-                if winning_nums.contains(&digit) {
-                    matched_numbers += 1;
-                }
-            } else {
-                winning_nums.insert(digit);
-            }
-        } else if part == "|" {
-            seen_all_winning_numbers = true;
-        }
-    }
-    matched_numbers
+/// Same as [`process`], counting each line's
+/// matches with the original solution,
+/// [`matches_with_hash_set`](crate::card::matches_with_hash_set)
+pub fn process_hash_set(
+    input: &str,
+) -> Result<u64, AocError> {
+    total_cards(&match_counts_with_hash_set(input))
 }
 
-fn calculate_total_num_cards(matches: Vec<i32>) -> i32 {
-    let nr_games = matches.len();
-    // create array of 1's of length nr_games
-    let mut copies_won = vec![1; nr_games];
-    for i in 0..nr_games {
-        let matches_this_game = matches[i];
-        let copies_this_game = copies_won[i];
-        copies_won
+/// Same as [`process`], reading one line at a
+/// time. A card only wins copies of the next few
+/// cards, so only the extra copies already won by
+/// those cards are kept rather than every card.
+pub fn process_reader(
+    reader: impl BufRead,
+) -> miette::Result<u64, AocError> {
+    let mut extra_copies: VecDeque<u64> = VecDeque::new();
+    let mut total = 0_u64;
+    let mut card = 0;
+    try_for_each_line(reader, |line| {
+        let scratchcard = Scratchcard::parse(line)?;
+        card += 1;
+        check_id(&scratchcard, card)?;
+        let matches = scratchcard.matches();
+        let copies = add(
+            1,
+            extra_copies.pop_front().unwrap_or(0),
+            "copies + won",
+            || format!("counting copies of card {card}"),
+        )?;
+        total =
+            add(total, copies, "total + copies", || {
+                format!("adding up {card} cards")
+            })?;
+        if extra_copies.len() < matches {
+            extra_copies.resize(matches, 0);
+        }
+        for (offset, extra) in extra_copies
             .iter_mut()
-            .skip(i + 1)
-            .take(matches_this_game as usize)
-            .for_each(|x| *x += copies_this_game);
-    }
-    copies_won.iter().sum()
+            .take(matches)
+            .enumerate()
+        {
+            *extra = add(
+                *extra,
+                copies,
+                "copies + won",
+                || {
+                    format!(
+                        "card {card} won copies of card {}",
+                        card as usize + offset + 1
+                    )
+                },
+            )?;
+        }
+        Ok::<_, AocError>(())
+    })?;
+    debug!(total, "total number of cards");
+    Ok(total)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cascade::{doubling, synthetic_input};
+    use aoc_common::{
+        lines::assert_all_formats,
+        variants::{
+            assert_variants, assert_variants_agree_on_input,
+        },
+    };
 
     #[test]
     fn test_process() -> miette::Result<()> {
@@ -70,7 +113,47 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        assert_eq!(30, process(input)?);
+        assert_variants(VARIANTS, input, 30);
+        assert_eq!(30, process_reader(input.as_bytes())?);
+        assert_all_formats(
+            |input| process_reader(input.as_bytes()),
+            input,
+            30,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_process_reader_agrees() -> miette::Result<()> {
+        let input = synthetic_input(&doubling(64))?;
+        assert_eq!(u64::MAX, process(&input)?);
+        assert_eq!(
+            process(&input)?,
+            process_reader(input.as_bytes())?
+        );
         Ok(())
     }
+
+    #[test]
+    fn test_overflow() {
+        let input = synthetic_input(&doubling(65)).unwrap();
+        for result in [
+            process(&input),
+            process_reader(input.as_bytes()),
+        ] {
+            let Err(AocError::Overflow(overflow)) = result
+            else {
+                panic!("expected an overflow");
+            };
+            assert_eq!("copies + won", overflow.operation);
+        }
+    }
+
+    #[test]
+    fn test_variants_agree_on_input() {
+        assert_variants_agree_on_input(
+            VARIANTS,
+            env!("CARGO_PKG_NAME"),
+        );
+    }
 }
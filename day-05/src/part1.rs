@@ -1,11 +1,15 @@
 use crate::custom_error::AocError;
+use aoc_common::checked;
+use aoc_common::parse::{
+    integer, labelled, numbers, parse_all, PResult,
+};
 use nom::{
     bytes::complete::tag,
-    character::complete::{
-        alpha1, digit1, line_ending, space1,
-    },
-    multi::{many0, many1, separated_list1},
-    *,
+    character::complete::{alpha1, multispace0, space1},
+    combinator::cut,
+    error::context,
+    multi::many1,
+    sequence::{pair, preceded, tuple},
 };
 use std::collections::HashMap;
 use tracing::debug;
@@ -22,15 +26,15 @@ struct AlmanacEntry<'a> {
 }
 
 impl AlmanacEntry<'_> {
-    fn apply(&self, value: u64) -> u64 {
+    fn apply(&self, value: u64) -> Result<u64, AocError> {
         for map in &self.maps {
-            if let Some(result) = map.apply(value) {
+            if let Some(result) = map.apply(value)? {
                 debug!(self.from, ?value, self.to, ?result);
-                return result;
+                return Ok(result);
             }
         }
         debug!(self.from, ?value, self.to, ?value);
-        value
+        Ok(value)
     }
 }
 
@@ -42,41 +46,44 @@ struct RangeMap {
 }
 
 impl RangeMap {
-    fn apply(&self, value: u64) -> Option<u64> {
+    fn apply(
+        &self,
+        value: u64,
+    ) -> Result<Option<u64>, AocError> {
+        let source_end = checked!(
+            add(self.source_start, self.length),
+            "finding the end of {self:?}"
+        )?;
         if (self.source_start <= value)
-            & (value < self.source_start + self.length)
+            & (value < source_end)
         {
-            return Some(
-                self.destination_start + value
-                    - self.source_start,
-            );
+            // value is at least source_start, so only
+            // the offset into the destination can
+            // overflow
+            let offset = value - self.source_start;
+            return Ok(Some(checked!(
+                add(self.destination_start, offset),
+                "mapping {value} with {self:?}"
+            )?));
         }
-        None
+        Ok(None)
     }
 }
 
 #[tracing::instrument(skip(input))]
-fn parse_seeds(input: &str) -> IResult<&str, Seeds> {
-    let (input, _) = tag("seeds: ")(input)?;
-    let (input, seeds) =
-        separated_list1(space1, digit1)(input)?;
-    let seeds = Seeds {
-        seeds: seeds
-            .iter()
-            .map(|s| s.parse::<u64>().unwrap())
-            .collect(),
-    };
-
-    Ok((input, seeds))
+fn parse_seeds(input: &str) -> PResult<'_, Seeds> {
+    let (input, seeds) = labelled("seeds", numbers)(input)?;
+    Ok((input, Seeds { seeds }))
 }
 
 fn parse_all_entries(
     input: &str,
-) -> IResult<&str, HashMap<&str, AlmanacEntry>> {
+) -> PResult<'_, HashMap<&str, AlmanacEntry<'_>>> {
     let mut almanac_entires =
         HashMap::<&str, AlmanacEntry>::new();
     let (input, entries) =
         many1(parse_almanac_entry)(input)?;
+    let (input, _) = multispace0(input)?;
     for entry in entries {
         almanac_entires.insert(entry.from, entry);
     }
@@ -85,34 +92,38 @@ fn parse_all_entries(
 
 fn parse_almanac_entry(
     input: &str,
-) -> IResult<&str, AlmanacEntry> {
-    let (input, _) = many0(line_ending)(input)?;
+) -> PResult<'_, AlmanacEntry<'_>> {
+    let (input, _) = multispace0(input)?;
     let (input, (from, to)) = parse_almanac_name(input)?;
     let (input, _) = tag(" map:")(input)?;
-    let (input, maps) = many1(parse_map)(input)?;
+    let (input, maps) =
+        context("a range map", many1(parse_map))(input)?;
     Ok((input, AlmanacEntry { from, to, maps }))
 }
 
 fn parse_almanac_name(
     input: &str,
-) -> IResult<&str, (&str, &str)> {
+) -> PResult<'_, (&str, &str)> {
     let (input, from) = alpha1(input)?;
     let (input, _) = tag("-to-")(input)?;
     let (input, to) = alpha1(input)?;
     Ok((input, (from, to)))
 }
 
-fn parse_map(input: &str) -> IResult<&str, RangeMap> {
-    let (input, _) = many0(line_ending)(input)?;
-    let (input, destination) = digit1(input)?;
-    let (input, _) = space1(input)?;
-    let (input, source) = digit1(input)?;
-    let (input, _) = space1(input)?;
-    let (input, length) = digit1(input)?;
+fn parse_map(input: &str) -> PResult<'_, RangeMap> {
+    let (input, _) = multispace0(input)?;
+    let (input, (destination, source, length)) =
+        tuple((
+            integer::<u64>,
+            // a line starting with a number must be a
+            // whole map
+            cut(preceded(space1, integer::<u64>)),
+            cut(preceded(space1, integer::<u64>)),
+        ))(input)?;
     let map = RangeMap {
-        source_start: source.parse().unwrap(),
-        destination_start: destination.parse().unwrap(),
-        length: length.parse().unwrap(),
+        source_start: source,
+        destination_start: destination,
+        length,
     };
     Ok((input, map))
 }
@@ -120,15 +131,15 @@ fn parse_map(input: &str) -> IResult<&str, RangeMap> {
 fn map_seed_to_location(
     seed: u64,
     almanac: &HashMap<&str, AlmanacEntry>,
-) -> u64 {
+) -> Result<u64, AocError> {
     let mut from = "seed";
     let mut value = seed;
     loop {
         let entry = almanac.get(from).unwrap();
-        value = entry.apply(value);
+        value = entry.apply(value)?;
         from = entry.to;
         if from == "location" {
-            return value;
+            return Ok(value);
         }
     }
 }
@@ -137,24 +148,26 @@ fn map_seed_to_location(
 pub fn process(
     input: &str,
 ) -> miette::Result<u64, AocError> {
-    let (input, seeds) = parse_seeds(input).unwrap();
-    let (input, almanac) =
-        parse_all_entries(input).unwrap();
-    assert_eq!(input, "");
-    let min_location = seeds
+    let (seeds, almanac) = parse_all(
+        input,
+        pair(parse_seeds, parse_all_entries),
+    )?;
+    let locations = seeds
         .seeds
         .into_iter()
         .map(|seed| map_seed_to_location(seed, &almanac))
-        .reduce(
-            |acc, location| {
+        .collect::<Result<Vec<u64>, AocError>>()?;
+    let min_location =
+        locations
+            .into_iter()
+            .reduce(|acc, location| {
                 if location < acc {
                     location
                 } else {
                     acc
                 }
-            },
-        )
-        .unwrap();
+            })
+            .unwrap();
     Ok(min_location)
 }
 
@@ -163,23 +176,76 @@ mod tests {
     use itertools::Itertools;
 
     use super::*;
+    use aoc_common::lines::assert_all_formats;
 
     #[test]
-    fn test_map_apply() {
+    fn test_map_apply() -> miette::Result<()> {
         let map = RangeMap {
             source_start: 10,
             destination_start: 20,
             length: 10,
         };
-        assert_eq!(Some(20), map.apply(10));
-        assert_eq!(Some(21), map.apply(11));
-        assert_eq!(Some(29), map.apply(19));
-        assert_eq!(None, map.apply(20));
-        assert_eq!(None, map.apply(9));
+        assert_eq!(Some(20), map.apply(10)?);
+        assert_eq!(Some(21), map.apply(11)?);
+        assert_eq!(Some(29), map.apply(19)?);
+        assert_eq!(None, map.apply(20)?);
+        assert_eq!(None, map.apply(9)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_apply_near_limit() -> miette::Result<()> {
+        // destination_start + value would overflow
+        // before subtracting source_start
+        let map = RangeMap {
+            source_start: u64::MAX - 10,
+            destination_start: u64::MAX - 5,
+            length: 10,
+        };
+        assert_eq!(
+            Some(u64::MAX - 4),
+            map.apply(u64::MAX - 9)?
+        );
+        assert_eq!(None, map.apply(u64::MAX - 11)?);
+        Ok(())
+    }
+
+    #[cfg(feature = "checked-math")]
+    #[test]
+    fn test_map_apply_overflow() {
+        let past_end = RangeMap {
+            source_start: u64::MAX - 1,
+            destination_start: 0,
+            length: 10,
+        };
+        assert!(matches!(
+            past_end.apply(0),
+            Err(AocError::Overflow(_))
+        ));
+
+        let past_destination = RangeMap {
+            source_start: 0,
+            destination_start: u64::MAX - 1,
+            length: 10,
+        };
+        assert_eq!(
+            Some(u64::MAX),
+            past_destination.apply(1).unwrap()
+        );
+        let Err(AocError::Overflow(overflow)) =
+            past_destination.apply(2)
+        else {
+            panic!("expected an overflow");
+        };
+        assert_eq!(
+            "self.destination_start + offset",
+            overflow.operation
+        );
+        assert!(overflow.context.starts_with("mapping 2"));
     }
 
     #[test]
-    fn test_almanac_entry_apply() {
+    fn test_almanac_entry_apply() -> miette::Result<()> {
         let entry = AlmanacEntry {
             from: "soil",
             to: "fertilizer",
@@ -196,14 +262,15 @@ mod tests {
                 },
             ],
         };
-        assert_eq!(20, entry.apply(10));
-        assert_eq!(21, entry.apply(11));
-        assert_eq!(29, entry.apply(19));
-        assert_eq!(0, entry.apply(30));
-        assert_eq!(1, entry.apply(31));
-        assert_eq!(9, entry.apply(39));
-        assert_eq!(40, entry.apply(40));
-        assert_eq!(99999, entry.apply(99999));
+        assert_eq!(20, entry.apply(10)?);
+        assert_eq!(21, entry.apply(11)?);
+        assert_eq!(29, entry.apply(19)?);
+        assert_eq!(0, entry.apply(30)?);
+        assert_eq!(1, entry.apply(31)?);
+        assert_eq!(9, entry.apply(39)?);
+        assert_eq!(40, entry.apply(40)?);
+        assert_eq!(99999, entry.apply(99999)?);
+        Ok(())
     }
 
     #[test]
@@ -327,9 +394,25 @@ humidity-to-location map:
         )
     }
 
+    #[test]
+    fn test_parse_error() {
+        let input =
+            TEST_INPUT.replace("37 52 2", "37 52 x");
+        let Err(AocError::Parse(error)) = process(&input)
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!("a number", error.expected);
+        assert_eq!(
+            input.find('x').unwrap(),
+            error.offset()
+        );
+    }
+
     #[test_log::test]
     fn test_process() -> miette::Result<()> {
         assert_eq!(35, process(TEST_INPUT)?);
+        assert_all_formats(process, TEST_INPUT, 35);
         Ok(())
     }
 }
@@ -1,19 +1,38 @@
 use crate::custom_error::AocError;
+use aoc_common::checked;
+use aoc_common::parse::{
+    integer, labelled, parse_all, PResult,
+};
 use nom::{
     bytes::complete::tag,
-    character::complete::{
-        alpha1, digit1, line_ending, space1,
-    },
-    multi::{many0, many1, separated_list1},
-    sequence::separated_pair,
-    *,
+    character::complete::{alpha1, multispace0, space1},
+    combinator::cut,
+    error::context,
+    multi::{many1, separated_list1},
+    sequence::{pair, preceded, separated_pair, tuple},
 };
 use std::{collections::HashMap, ops::Range};
 use tracing::debug;
 
 #[derive(Debug)]
 struct Seeds {
-    seed_ranges: Vec<Range<u64>>,
+    /// `(start, length)` of each range of seeds
+    seed_ranges: Vec<(u64, u64)>,
+}
+
+impl Seeds {
+    fn ranges(&self) -> Result<Vec<Range<u64>>, AocError> {
+        self.seed_ranges
+            .iter()
+            .map(|&(start, length)| {
+                Ok(start
+                    ..checked!(
+                        add(start, length),
+                        "finding the end of the seeds {start} {length}"
+                    )?)
+            })
+            .collect()
+    }
 }
 
 struct AlmanacEntry<'a> {
@@ -23,14 +42,17 @@ struct AlmanacEntry<'a> {
 }
 
 impl AlmanacEntry<'_> {
-    fn apply(&self, range: Range<u64>) -> Vec<Range<u64>> {
+    fn apply(
+        &self,
+        range: Range<u64>,
+    ) -> Result<Vec<Range<u64>>, AocError> {
         let mut source_ranges = vec![range];
         let mut result_ranges = vec![];
         for map in &self.maps {
             let mut new_source_ranges = vec![];
             while let Some(_range) = source_ranges.pop() {
                 debug!(?_range, ?map, "applying map");
-                let map_result = map.apply(_range);
+                let map_result = map.apply(_range)?;
                 debug!(?map_result, "map result");
                 if let Some(below_range) =
                     map_result.below_range
@@ -54,14 +76,15 @@ impl AlmanacEntry<'_> {
         while let Some(_range) = source_ranges.pop() {
             result_ranges.push(_range);
         }
-        result_ranges
+        Ok(result_ranges)
     }
 }
 
 #[derive(Debug, PartialEq)]
 struct RangeMap {
-    source_range: Range<u64>,
-    destination_range: Range<u64>,
+    source_start: u64,
+    destination_start: u64,
+    length: u64,
 }
 
 #[derive(Debug, PartialEq)]
@@ -72,9 +95,28 @@ struct RangeMapResult {
 }
 
 impl RangeMap {
-    fn apply(&self, range: Range<u64>) -> RangeMapResult {
-        let below_range = range.start
-            ..self.source_range.start.min(range.end);
+    fn source_end(&self) -> Result<u64, AocError> {
+        Ok(checked!(
+            add(self.source_start, self.length),
+            "finding the end of {self:?}"
+        )?)
+    }
+
+    fn destination_end(&self) -> Result<u64, AocError> {
+        Ok(checked!(
+            add(self.destination_start, self.length),
+            "finding the end of {self:?}"
+        )?)
+    }
+
+    fn apply(
+        &self,
+        range: Range<u64>,
+    ) -> Result<RangeMapResult, AocError> {
+        let source_end = self.source_end()?;
+        let destination_end = self.destination_end()?;
+        let below_range =
+            range.start..self.source_start.min(range.end);
         let below_range = if below_range.is_empty() {
             None
         } else {
@@ -82,8 +124,7 @@ impl RangeMap {
         };
 
         let above_range =
-            self.source_range.end.max(range.start)
-                ..range.end;
+            source_end.max(range.start)..range.end;
         let above_range = if above_range.is_empty() {
             None
         } else {
@@ -91,55 +132,54 @@ impl RangeMap {
         };
 
         let result_source =
-            self.source_range.start.max(range.start)
-                ..self.source_range.end.min(range.end);
+            self.source_start.max(range.start)
+                ..source_end.min(range.end);
         let result = if result_source.is_empty() {
             None
         } else {
-            let result_start = self.destination_range.start
-                + result_source.start
-                - self.source_range.start;
-            let result_end = self.destination_range.end
-                + result_source.end
-                - self.source_range.end;
+            // result_source is inside the source range,
+            // so the result is inside the destination
+            // range and can't overflow
+            let result_start = self.destination_start
+                + (result_source.start - self.source_start);
+            let result_end = destination_end
+                - (source_end - result_source.end);
             Some(result_start..result_end)
         };
 
-        RangeMapResult {
+        Ok(RangeMapResult {
             below_range,
             above_range,
             result,
-        }
+        })
     }
 }
 
 fn parse_seed_range(
     input: &str,
-) -> IResult<&str, Range<u64>> {
-    let (input, (start, length)) =
-        separated_pair(digit1, space1, digit1)(input)?;
-    let start = start.parse::<u64>().unwrap();
-    Ok((
+) -> PResult<'_, (u64, u64)> {
+    separated_pair(integer::<u64>, space1, integer::<u64>)(
         input,
-        start..start + length.parse::<u64>().unwrap(),
-    ))
+    )
 }
 
 #[tracing::instrument(skip(input))]
-fn parse_seeds(input: &str) -> IResult<&str, Seeds> {
-    let (input, _) = tag("seeds: ")(input)?;
-    let (input, seed_ranges) =
-        separated_list1(space1, parse_seed_range)(input)?;
+fn parse_seeds(input: &str) -> PResult<'_, Seeds> {
+    let (input, seed_ranges) = labelled(
+        "seeds",
+        separated_list1(space1, parse_seed_range),
+    )(input)?;
     Ok((input, Seeds { seed_ranges }))
 }
 
 fn parse_all_entries(
     input: &str,
-) -> IResult<&str, HashMap<&str, AlmanacEntry>> {
+) -> PResult<'_, HashMap<&str, AlmanacEntry<'_>>> {
     let mut almanac_entires =
         HashMap::<&str, AlmanacEntry>::new();
     let (input, entries) =
         many1(parse_almanac_entry)(input)?;
+    let (input, _) = multispace0(input)?;
     for entry in entries {
         almanac_entires.insert(entry.from, entry);
     }
@@ -148,38 +188,38 @@ fn parse_all_entries(
 
 fn parse_almanac_entry(
     input: &str,
-) -> IResult<&str, AlmanacEntry> {
-    let (input, _) = many0(line_ending)(input)?;
+) -> PResult<'_, AlmanacEntry<'_>> {
+    let (input, _) = multispace0(input)?;
     let (input, (from, to)) = parse_almanac_name(input)?;
     let (input, _) = tag(" map:")(input)?;
-    let (input, maps) = many1(parse_map)(input)?;
+    let (input, maps) =
+        context("a range map", many1(parse_map))(input)?;
     Ok((input, AlmanacEntry { from, to, maps }))
 }
 
 fn parse_almanac_name(
     input: &str,
-) -> IResult<&str, (&str, &str)> {
+) -> PResult<'_, (&str, &str)> {
     let (input, from) = alpha1(input)?;
     let (input, _) = tag("-to-")(input)?;
     let (input, to) = alpha1(input)?;
     Ok((input, (from, to)))
 }
 
-fn parse_map(input: &str) -> IResult<&str, RangeMap> {
-    let (input, _) = many0(line_ending)(input)?;
-    let (input, destination) = digit1(input)?;
-    let (input, _) = space1(input)?;
-    let (input, source) = digit1(input)?;
-    let (input, _) = space1(input)?;
-    let (input, length) = digit1(input)?;
-    let range_length = length.parse::<u64>().unwrap();
-    let source_start = source.parse::<u64>().unwrap();
-    let destination_start = destination.parse().unwrap();
+fn parse_map(input: &str) -> PResult<'_, RangeMap> {
+    let (input, _) = multispace0(input)?;
+    let (input, (destination_start, source_start, length)) =
+        tuple((
+            integer::<u64>,
+            // a line starting with a number must be a
+            // whole map
+            cut(preceded(space1, integer::<u64>)),
+            cut(preceded(space1, integer::<u64>)),
+        ))(input)?;
     let map = RangeMap {
-        source_range: source_start
-            ..source_start + range_length,
-        destination_range: destination_start
-            ..destination_start + range_length,
+        source_start,
+        destination_start,
+        length,
     };
     Ok((input, map))
 }
@@ -187,18 +227,19 @@ fn parse_map(input: &str) -> IResult<&str, RangeMap> {
 fn map_seed_range_to_location_ranges(
     seed_range: Range<u64>,
     almanac: &HashMap<&str, AlmanacEntry>,
-) -> Vec<Range<u64>> {
+) -> Result<Vec<Range<u64>>, AocError> {
     let mut from = "seed";
     let mut result_ranges = vec![seed_range];
     loop {
         let entry = almanac.get(from).unwrap();
-        result_ranges = result_ranges
-            .iter()
-            .flat_map(|range| entry.apply(range.clone()))
-            .collect();
+        let mut next_ranges = vec![];
+        for range in result_ranges {
+            next_ranges.extend(entry.apply(range)?);
+        }
+        result_ranges = next_ranges;
         from = entry.to;
         if from == "location" {
-            return result_ranges;
+            return Ok(result_ranges);
         }
     }
 }
@@ -207,23 +248,21 @@ fn map_seed_range_to_location_ranges(
 pub fn process(
     input: &str,
 ) -> miette::Result<u64, AocError> {
-    let (input, seeds) = parse_seeds(input).unwrap();
-    let (input, almanac) =
-        parse_all_entries(input).unwrap();
-    assert_eq!(input, "");
-    let min_location = seeds
-        .seed_ranges
-        .into_iter()
-        .flat_map(|seed| {
-            let maps = map_seed_range_to_location_ranges(
-                seed, &almanac,
-            );
-            debug!(?maps);
-            maps
-        })
-        .map(|r| r.start)
-        .min()
-        .unwrap();
+    let (seeds, almanac) = parse_all(
+        input,
+        pair(parse_seeds, parse_all_entries),
+    )?;
+    let mut min_location = u64::MAX;
+    for seed in seeds.ranges()? {
+        let maps = map_seed_range_to_location_ranges(
+            seed, &almanac,
+        )?;
+        debug!(?maps);
+        min_location = maps
+            .iter()
+            .map(|r| r.start)
+            .fold(min_location, u64::min);
+    }
     Ok(min_location)
 }
 
@@ -232,71 +271,77 @@ mod tests {
     use itertools::Itertools;
 
     use super::*;
+    use aoc_common::lines::assert_all_formats;
 
     #[test]
-    fn test_map_apply() {
+    fn test_map_apply() -> miette::Result<()> {
         let map = RangeMap {
-            source_range: 10..20,
-            destination_range: 20..30,
+            source_start: 10,
+            destination_start: 20,
+            length: 10,
         };
         let expected_result = RangeMapResult {
             below_range: None,
             above_range: None,
             result: Some(20..30),
         };
-        assert_eq!(expected_result, map.apply(10..20));
+        assert_eq!(expected_result, map.apply(10..20)?);
 
         let expected_result = RangeMapResult {
             below_range: Some(0..10),
             above_range: Some(20..40),
             result: Some(20..30),
         };
-        assert_eq!(expected_result, map.apply(0..40));
+        assert_eq!(expected_result, map.apply(0..40)?);
 
         let expected_result = RangeMapResult {
             below_range: None,
             above_range: None,
             result: Some(22..26),
         };
-        assert_eq!(expected_result, map.apply(12..16));
+        assert_eq!(expected_result, map.apply(12..16)?);
 
         let expected_result = RangeMapResult {
             below_range: Some(2..8),
             above_range: None,
             result: None,
         };
-        assert_eq!(expected_result, map.apply(2..8));
+        assert_eq!(expected_result, map.apply(2..8)?);
 
         let expected_result = RangeMapResult {
             below_range: None,
             above_range: Some(101..129),
             result: None,
         };
-        assert_eq!(expected_result, map.apply(101..129));
+        assert_eq!(expected_result, map.apply(101..129)?);
+        Ok(())
     }
 
     #[test_log::test]
-    fn test_almanac_entry_apply() {
+    fn test_almanac_entry_apply() -> miette::Result<()> {
         let entry = AlmanacEntry {
             from: "soil",
             to: "fertilizer",
             maps: vec![
                 RangeMap {
-                    source_range: 98..100,
-                    destination_range: 50..52,
+                    source_start: 98,
+                    destination_start: 50,
+                    length: 2,
                 },
                 RangeMap {
-                    source_range: 50..98,
-                    destination_range: 52..100,
+                    source_start: 50,
+                    destination_start: 52,
+                    length: 48,
                 },
             ],
         };
-        assert_eq!(vec![81..95], entry.apply(79..93));
-        assert_eq!(vec![57..70], entry.apply(55..68));
+        assert_eq!(vec![81..95], entry.apply(79..93)?);
+        assert_eq!(vec![57..70], entry.apply(55..68)?);
         assert_eq!(
             vec![50..52, 52..100, 0..50, 100..150],
-            entry.apply(0..150)
+            entry.apply(0..150)?
         );
+        Ok(())
     }
 
     #[test]
@@ -304,7 +349,85 @@ mod tests {
         let input = "seeds: 79 14 55 13";
         let (input, seeds) = parse_seeds(input).unwrap();
         assert_eq!(input, "");
-        assert_eq!(seeds.seed_ranges, vec![79..93, 55..68]);
+        assert_eq!(
+            seeds.seed_ranges,
+            vec![(79, 14), (55, 13)]
+        );
+        assert_eq!(
+            seeds.ranges().unwrap(),
+            vec![79..93, 55..68]
+        );
+    }
+
+    #[test]
+    fn test_ranges_near_limit() -> miette::Result<()> {
+        let seeds = Seeds {
+            seed_ranges: vec![(u64::MAX - 3, 3)],
+        };
+        assert_eq!(
+            vec![u64::MAX - 3..u64::MAX],
+            seeds.ranges()?
+        );
+        let map = RangeMap {
+            source_start: u64::MAX - 10,
+            destination_start: u64::MAX - 5,
+            length: 5,
+        };
+        assert_eq!(
+            Some(u64::MAX - 4..u64::MAX),
+            map.apply(u64::MAX - 9..u64::MAX)?.result
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "checked-math")]
+    #[test]
+    fn test_overflow() {
+        let seeds = Seeds {
+            seed_ranges: vec![(u64::MAX - 1, 2)],
+        };
+        let Err(AocError::Overflow(overflow)) =
+            seeds.ranges()
+        else {
+            panic!("expected an overflow");
+        };
+        assert_eq!("start + length", overflow.operation);
+
+        let past_source_end = RangeMap {
+            source_start: u64::MAX - 1,
+            destination_start: 0,
+            length: 10,
+        };
+        assert!(matches!(
+            past_source_end.apply(0..1),
+            Err(AocError::Overflow(_))
+        ));
+        let past_destination_end = RangeMap {
+            source_start: 0,
+            destination_start: u64::MAX - 1,
+            length: 10,
+        };
+        let Err(AocError::Overflow(overflow)) =
+            past_destination_end.apply(0..1)
+        else {
+            panic!("expected an overflow");
+        };
+        assert_eq!(
+            "self.destination_start + self.length",
+            overflow.operation
+        );
+
+        let input = "seeds: 18446744073709551615 1
+
+seed-to-soil map:
+0 0 1
+
+soil-to-location map:
+0 0 1";
+        assert!(matches!(
+            process(input),
+            Err(AocError::Overflow(_))
+        ));
     }
 
     #[test]
@@ -315,8 +438,9 @@ mod tests {
         assert_eq!(
             map,
             RangeMap {
-                source_range: 98..100,
-                destination_range: 50..52,
+                source_start: 98,
+                destination_start: 50,
+                length: 2,
             }
         );
     }
@@ -345,15 +469,17 @@ mod tests {
         assert_eq!(
             entry.maps[0],
             RangeMap {
-                source_range: 98..100,
-                destination_range: 50..52,
+                source_start: 98,
+                destination_start: 50,
+                length: 2,
             }
         );
         assert_eq!(
             entry.maps[1],
             RangeMap {
-                source_range: 50..98,
-                destination_range: 52..100,
+                source_start: 50,
+                destination_start: 52,
+                length: 48,
             }
         );
     }
@@ -420,6 +546,7 @@ humidity-to-location map:
     #[test_log::test]
     fn test_process() -> miette::Result<()> {
         assert_eq!(46, process(TEST_INPUT)?);
+        assert_all_formats(process, TEST_INPUT, 46);
         Ok(())
     }
 }
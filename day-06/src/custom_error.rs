@@ -6,4 +6,12 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Overflow(#[from] aoc_common::checked::Overflow),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Parse(#[from] aoc_common::parse::ParseError),
 }
@@ -1,4 +1,11 @@
-use std::io::BufRead;
+use aoc_common::{
+    checked,
+    parse::{end_of_line, labelled, numbers, parse_all},
+};
+use nom::{
+    combinator::verify, error::context,
+    sequence::separated_pair,
+};
 
 use crate::custom_error::AocError;
 struct Race<T> {
@@ -11,15 +18,20 @@ impl Race<i64> {
         Self { time, distance }
     }
 
-    fn ways_to_win(&self) -> i64 {
+    fn ways_to_win(&self) -> Result<i64, AocError> {
         let mut i = 1;
         while (i * 2) <= self.time {
-            if i * (self.time - i) > self.distance {
+            let distance = checked!(
+                mul(i, self.time - i),
+                "holding the button for {i}ms of a {}ms race",
+                self.time
+            )?;
+            if distance > self.distance {
                 break;
             }
             i += 1;
         }
-        self.time - i - i + 1
+        Ok(self.time - i - i + 1)
     }
 }
 
@@ -27,7 +39,6 @@ impl Race<i64> {
 pub fn process(
     input: &str,
 ) -> miette::Result<i64, AocError> {
-    let input = input.as_bytes();
     let (times, distances) = parse_input(input)?;
 
     let time2 = times
@@ -43,48 +54,56 @@ pub fn process(
         .parse::<i64>()
         .unwrap();
     let race2 = Race::new(time2, distance2);
-    Ok(race2.ways_to_win())
+    race2.ways_to_win()
 }
 
 fn parse_input(
-    input: &[u8],
+    input: &str,
 ) -> miette::Result<(Vec<i64>, Vec<i64>), AocError> {
-    let mut lines = input.lines();
-    let first_line =
-        lines.next().expect("Invalid input")?;
-    let mut first_line = first_line.split_whitespace();
-    let times = if let Some("Time:") = first_line.next() {
-        first_line
-            .map(|x| x.parse::<i64>().unwrap())
-            .collect::<Vec<i64>>()
-    } else {
-        panic!("Invalid input");
-    };
-    let second_line =
-        lines.next().expect("Invalid input")?;
-    let mut second_line = second_line.split_whitespace();
-    let distances =
-        if let Some("Distance:") = second_line.next() {
-            second_line
-                .map(|x| x.parse::<i64>().unwrap())
-                .collect::<Vec<i64>>()
-        } else {
-            panic!("Invalid input");
-        };
-    assert!(lines.next().is_none());
-    assert_eq!(times.len(), distances.len());
-    Ok((times, distances))
+    let races = verify(
+        separated_pair(
+            labelled("Time", numbers),
+            end_of_line,
+            labelled("Distance", numbers),
+        ),
+        |(times, distances): &(Vec<i64>, Vec<i64>)| {
+            times.len() == distances.len()
+        },
+    );
+    Ok(parse_all(
+        input,
+        context("a distance for every time", races),
+    )?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::lines::assert_all_formats;
 
     #[test]
     fn test_process() -> miette::Result<()> {
         let input = "Time:      7  15   30
 Distance:  9  40  200";
         assert_eq!(71503, process(input)?);
+        assert_all_formats(process, input, 71503);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ways_to_win_near_limit() -> miette::Result<()> {
+        let race = Race::new(i64::MAX, 0);
+        assert_eq!(i64::MAX - 1, race.ways_to_win()?);
         Ok(())
     }
+
+    #[cfg(feature = "checked-math")]
+    #[test]
+    fn test_ways_to_win_overflow() {
+        let race = Race::new(i64::MAX, i64::MAX);
+        assert!(matches!(
+            race.ways_to_win(),
+            Err(AocError::Overflow(_))
+        ));
+    }
 }
@@ -0,0 +1,9 @@
+use day_11::visualize::Expansion;
+
+fn main() -> miette::Result<()> {
+    let file = aoc_common::input::load_day(env!(
+        "CARGO_PKG_NAME"
+    ))?;
+    aoc_common::visualize::main(&Expansion::new(&file))?;
+    Ok(())
+}
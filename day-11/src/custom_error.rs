@@ -6,4 +6,15 @@ pub enum AocError {
     #[error(transparent)]
     #[diagnostic(code(aoc::io_error))]
     IoError(#[from] std::io::Error),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Overflow(#[from] aoc_common::checked::Overflow),
+
+    #[error("can't expand empty space by a factor of {0}")]
+    #[diagnostic(
+        code(aoc::factor_too_small),
+        help("the factor must be at least 1, which leaves the universe as it is")
+    )]
+    FactorTooSmall(u64),
 }
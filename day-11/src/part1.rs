@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use crate::custom_error::AocError;
+use aoc_common::checked;
 use glam::UVec2;
 use itertools::Itertools;
 use nom::{
@@ -11,28 +12,39 @@ use nom::{
 use nom_locate::{position, LocatedSpan};
 use tracing::debug;
 
+/// A galaxy at its 1-based line (`x`) and column
+/// (`y`)
 #[derive(Debug)]
-struct Galaxy {
-    position: UVec2,
+pub(crate) struct Galaxy {
+    pub(crate) position: UVec2,
 }
 
 #[tracing::instrument(skip(input))]
 pub fn process(
     input: &str,
 ) -> miette::Result<u32, AocError> {
-    let input = input.as_bytes();
-    let (input_remainder, galaxies) =
-        parse_input(input).unwrap();
-    assert_eq!(input_remainder.fragment(), b"");
+    let galaxies = galaxies(input);
     debug!(?galaxies, "parsed galaxies");
     let expanded_galaxies = expand_galaxies(&galaxies);
     debug!(?expanded_galaxies, "expanded galaxies");
-    let all_pairs_distance_sum = expanded_galaxies
+    sum_distances(&expanded_galaxies)
+}
+
+pub(crate) fn galaxies(input: &str) -> Vec<Galaxy> {
+    let (input_remainder, galaxies) =
+        parse_input(input.as_bytes()).unwrap();
+    assert_eq!(input_remainder.fragment(), b"");
+    galaxies
+}
+
+/// Sum the manhattan distance between every pair
+fn sum_distances(
+    galaxies: &[Galaxy],
+) -> Result<u32, AocError> {
+    galaxies
         .iter()
         .enumerate()
-        .cartesian_product(
-            expanded_galaxies.iter().enumerate(),
-        )
+        .cartesian_product(galaxies.iter().enumerate())
         .filter_map(|((i, g1), (j, g2))| match i.cmp(&j) {
             std::cmp::Ordering::Less => {
                 let dx =
@@ -43,11 +55,25 @@ pub fn process(
             }
             _ => None,
         })
-        .sum::<u32>();
-    Ok(all_pairs_distance_sum)
+        .try_fold(0_u32, |sum, distance| {
+            Ok(checked!(
+                add(sum, distance),
+                "summing distances between {} galaxies",
+                galaxies.len()
+            )?)
+        })
 }
 
 fn expand_galaxies(galaxies: &[Galaxy]) -> Vec<Galaxy> {
+    let (empty_rows, empty_cols) = empty_space(galaxies);
+    expand_by(galaxies, &empty_rows, &empty_cols)
+}
+
+/// The rows and columns with no galaxy in them,
+/// up to the last galaxy, in order
+pub(crate) fn empty_space(
+    galaxies: &[Galaxy],
+) -> (Vec<u32>, Vec<u32>) {
     let rows_with_galaxies = galaxies
         .iter()
         .map(|g| g.position.x)
@@ -56,39 +82,37 @@ fn expand_galaxies(galaxies: &[Galaxy]) -> Vec<Galaxy> {
         .iter()
         .map(|g| g.position.y)
         .collect::<HashSet<u32>>();
-    let max_row = *rows_with_galaxies.iter().max().unwrap();
-    let max_col = *cols_with_galaxies.iter().max().unwrap();
-
-    let mut expanded_rows_count = 0;
-    let mut expanded_cols_count = 0;
-
-    let row_expansion_amount = (1..=max_row)
-        .map(|row| {
-            if !rows_with_galaxies.contains(&row) {
-                expanded_rows_count += 1;
-            }
-            expanded_rows_count
-        })
-        .collect::<Vec<u32>>();
-    let col_expansion_amount = (1..=max_col)
-        .map(|col| {
-            if !cols_with_galaxies.contains(&col) {
-                expanded_cols_count += 1;
-            }
-            expanded_cols_count
-        })
-        .collect::<Vec<u32>>();
+    let max_row = rows_with_galaxies.iter().max().copied();
+    let max_col = cols_with_galaxies.iter().max().copied();
+    (
+        (1..=max_row.unwrap_or(0))
+            .filter(|row| !rows_with_galaxies.contains(row))
+            .collect(),
+        (1..=max_col.unwrap_or(0))
+            .filter(|col| !cols_with_galaxies.contains(col))
+            .collect(),
+    )
+}
 
+/// Move each galaxy along by one for every row in
+/// `empty_rows` and column in `empty_cols` before
+/// it, both in order
+pub(crate) fn expand_by(
+    galaxies: &[Galaxy],
+    empty_rows: &[u32],
+    empty_cols: &[u32],
+) -> Vec<Galaxy> {
+    let before = |empty: &[u32], at: u32| {
+        empty.partition_point(|&e| e < at) as u32
+    };
     galaxies
         .iter()
         .map(|g| Galaxy {
             position: UVec2::new(
                 g.position.x
-                    + row_expansion_amount
-                        [(g.position.x - 1) as usize],
+                    + before(empty_rows, g.position.x),
                 g.position.y
-                    + col_expansion_amount
-                        [(g.position.y - 1) as usize],
+                    + before(empty_cols, g.position.y),
             ),
         })
         .collect()
@@ -100,7 +124,11 @@ fn parse_input(
     let span = LocatedSpan::new(input);
     let (span, galaxies) =
         many0(parse_galaxy)(span).expect("");
-    let (span, _) = take_while(|c| c == b'.')(span)?;
+    // empty space after the last galaxy, including
+    // line endings and trailing whitespace
+    let (span, _) = take_while(|c: u8| {
+        c == b'.' || c.is_ascii_whitespace()
+    })(span)?;
     Ok((span, galaxies))
 }
 
@@ -120,6 +148,7 @@ fn parse_galaxy(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::lines::assert_all_formats;
 
     #[test]
     fn test_parse_galaxy() {
@@ -213,6 +242,42 @@ mod tests {
     #[test_log::test]
     fn test_process() -> miette::Result<()> {
         assert_eq!(374, process(TEST_INPUT)?);
+        assert_all_formats(process, TEST_INPUT, 374);
         Ok(())
     }
+
+    fn galaxies(positions: &[(u32, u32)]) -> Vec<Galaxy> {
+        positions
+            .iter()
+            .map(|&(x, y)| Galaxy {
+                position: UVec2::new(x, y),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sum_distances_near_limit() -> miette::Result<()>
+    {
+        assert_eq!(
+            u32::MAX,
+            sum_distances(&galaxies(&[
+                (0, 0),
+                (0, u32::MAX)
+            ]))?
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "checked-math")]
+    #[test]
+    fn test_sum_distances_overflow() {
+        assert!(matches!(
+            sum_distances(&galaxies(&[
+                (0, 0),
+                (0, 2),
+                (0, u32::MAX)
+            ])),
+            Err(AocError::Overflow(_))
+        ));
+    }
 }
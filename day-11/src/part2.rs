@@ -10,6 +10,16 @@ use nom::{
 use nom_locate::{position, LocatedSpan};
 use tracing::debug;
 
+use crate::custom_error::AocError;
+
+aoc_common::params! {
+    pub struct Params {
+        /// how many times larger each empty row and
+        /// column becomes
+        factor: u64 = 1_000_000,
+    }
+}
+
 #[derive(Debug)]
 struct Galaxy {
     position: U64Vec2,
@@ -17,20 +27,29 @@ struct Galaxy {
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<u64> {
-    _process(input, 1_000_000)
+    process_with(input, &Params::default())
 }
 
-fn _process(
+/// Same as [`process`], expanding empty space by
+/// the factor in `params`, which must be at least
+/// 1
+pub fn process_with(
     input: &str,
-    factor: u64,
+    params: &Params,
 ) -> miette::Result<u64> {
+    if params.factor < 1 {
+        return Err(AocError::FactorTooSmall(
+            params.factor,
+        )
+        .into());
+    }
     let input = input.as_bytes();
     let (input_remainder, galaxies) =
         parse_input(input).unwrap();
     assert_eq!(input_remainder.fragment(), b"");
     debug!(?galaxies, "parsed galaxies");
     let expanded_galaxies =
-        expand_galaxies(&galaxies, factor);
+        expand_galaxies(&galaxies, params.factor);
     debug!(?expanded_galaxies, "expanded galaxies");
     let all_pairs_distance_sum = expanded_galaxies
         .iter()
@@ -108,7 +127,11 @@ fn parse_input(
     let span = LocatedSpan::new(input);
     let (span, galaxies) =
         many0(parse_galaxy)(span).expect("");
-    let (span, _) = take_while(|c| c == b'.')(span)?;
+    // empty space after the last galaxy, including
+    // line endings and trailing whitespace
+    let (span, _) = take_while(|c: u8| {
+        c == b'.' || c.is_ascii_whitespace()
+    })(span)?;
     Ok((span, galaxies))
 }
 
@@ -128,6 +151,9 @@ fn parse_galaxy(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_common::{
+        lines::assert_all_formats, params::Params as _,
+    };
 
     #[test]
     fn test_parse_galaxy() {
@@ -242,8 +268,53 @@ mod tests {
 
     #[test_log::test]
     fn test_process() -> miette::Result<()> {
-        assert_eq!(1030, _process(TEST_INPUT, 10)?);
-        assert_eq!(8410, _process(TEST_INPUT, 100)?);
+        let params = Params::with(["factor=10"])?;
+        assert_eq!(
+            1030,
+            process_with(TEST_INPUT, &params)?
+        );
+        assert_all_formats(
+            |input| process_with(input, &params),
+            TEST_INPUT,
+            1030,
+        );
+        assert_eq!(
+            8410,
+            process_with(
+                TEST_INPUT,
+                &Params { factor: 100 }
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_factor_too_small() -> miette::Result<()> {
+        let error = process_with(
+            TEST_INPUT,
+            &Params::with(["factor=0"])?,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<AocError>(),
+            Some(AocError::FactorTooSmall(0))
+        ));
+        // 1 leaves empty space as it is, and 2 is
+        // part 1
+        assert_eq!(
+            292,
+            process_with(
+                TEST_INPUT,
+                &Params { factor: 1 }
+            )?
+        );
+        assert_eq!(
+            374,
+            process_with(
+                TEST_INPUT,
+                &Params { factor: 2 }
+            )?
+        );
         Ok(())
     }
 }
@@ -0,0 +1,233 @@
+//! The universe expanding one empty row or column
+//! at a time, with galaxies in yellow and the
+//! inserted space highlighted.
+use std::time::Duration;
+
+use aoc_common::{
+    lines::input_lines,
+    visualize::{Frame, Rgb, Visualize},
+};
+
+use crate::part1::{
+    empty_space, expand_by, galaxies, Galaxy,
+};
+
+/// The universe, drawn from the galaxies part 1
+/// parses and moved with part 1's expansion so
+/// the animation shows the answer's distances
+pub struct Expansion {
+    width: usize,
+    height: usize,
+    galaxies: Vec<Galaxy>,
+}
+
+impl Expansion {
+    pub fn new(input: &str) -> Self {
+        let (width, height) = input_lines(input).fold(
+            (0, 0),
+            |(width, height), line| {
+                (width.max(line.len()), height + 1)
+            },
+        );
+        Self {
+            width,
+            height,
+            galaxies: galaxies(input),
+        }
+    }
+}
+
+/// The 0-based lines of the empty rows (or
+/// columns) in `empty` once the first `doubled`
+/// of them have been doubled
+fn marked(empty: &[u32], doubled: usize) -> Vec<usize> {
+    empty
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &line)| {
+            let at = line as usize - 1 + i.min(doubled);
+            if i < doubled {
+                vec![at, at + 1]
+            } else {
+                vec![at]
+            }
+        })
+        .collect()
+}
+
+/// The universe part way through expanding, with
+/// the rows and columns to highlight
+struct Universe<'a> {
+    width: usize,
+    height: usize,
+    galaxies: &'a [Galaxy],
+    marked_rows: Vec<usize>,
+    marked_cols: Vec<usize>,
+}
+
+impl Universe<'_> {
+    fn frame(&self, caption: String) -> Frame {
+        let mut frame = Frame::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                frame.set(x, y, '.', Rgb::DIM);
+                if self.marked_rows.contains(&y)
+                    || self.marked_cols.contains(&x)
+                {
+                    frame.mark(x, y, Rgb::BLUE);
+                }
+            }
+        }
+        for galaxy in self.galaxies {
+            frame.set(
+                galaxy.position.y as usize - 1,
+                galaxy.position.x as usize - 1,
+                '#',
+                Rgb::YELLOW,
+            );
+        }
+        frame.caption(caption);
+        frame
+    }
+}
+
+impl Visualize for Expansion {
+    fn frames(&self) -> Vec<Frame> {
+        let (empty_rows, empty_cols) =
+            empty_space(&self.galaxies);
+        let universe = Universe {
+            width: self.width,
+            height: self.height,
+            galaxies: &self.galaxies,
+            marked_rows: vec![],
+            marked_cols: vec![],
+        };
+        let mut frames = vec![universe.frame(format!(
+            "{} galaxies",
+            self.galaxies.len()
+        ))];
+        frames.push(
+            Universe {
+                marked_rows: marked(&empty_rows, 0),
+                marked_cols: marked(&empty_cols, 0),
+                ..universe
+            }
+            .frame(format!(
+                "{} empty rows and {} empty columns",
+                empty_rows.len(),
+                empty_cols.len()
+            )),
+        );
+
+        for (i, &y) in empty_rows.iter().enumerate() {
+            let doubled = &empty_rows[..=i];
+            frames.push(
+                Universe {
+                    height: self.height + doubled.len(),
+                    galaxies: &expand_by(
+                        &self.galaxies,
+                        doubled,
+                        &[],
+                    ),
+                    marked_rows: marked(
+                        &empty_rows,
+                        doubled.len(),
+                    ),
+                    marked_cols: marked(&empty_cols, 0),
+                    ..universe
+                }
+                .frame(format!("doubled row {y}")),
+            );
+        }
+        let height = self.height + empty_rows.len();
+        for (i, &x) in empty_cols.iter().enumerate() {
+            let doubled = &empty_cols[..=i];
+            frames.push(
+                Universe {
+                    width: self.width + doubled.len(),
+                    height,
+                    galaxies: &expand_by(
+                        &self.galaxies,
+                        &empty_rows,
+                        doubled,
+                    ),
+                    marked_rows: marked(
+                        &empty_rows,
+                        empty_rows.len(),
+                    ),
+                    marked_cols: marked(
+                        &empty_cols,
+                        doubled.len(),
+                    ),
+                }
+                .frame(format!("doubled column {x}")),
+            );
+        }
+
+        let last = frames.len() - 1;
+        frames[last].caption(format!(
+            "expanded from {}x{} to {}x{height}",
+            self.width,
+            self.height,
+            self.width + empty_cols.len(),
+        ));
+        frames[last].delay = Duration::from_secs(2);
+        frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    #[test]
+    fn test_frames() {
+        let frames = Expansion::new(TEST_INPUT).frames();
+        // start, empty space marked, 2 rows and 3
+        // columns inserted
+        assert_eq!(7, frames.len());
+        assert_eq!(
+            "....#........
+.........#...
+#............
+.............
+.............
+........#....
+.#...........
+............#
+.............
+.............
+.........#...
+#....#.......
+doubled column 9
+expanded from 10x10 to 13x12
+",
+            frames[6].to_ascii()
+        );
+        assert_eq!(
+            Some(Rgb::BLUE),
+            frames[1].get(2, 0).unwrap().bg
+        );
+        assert_eq!(None, frames[1].get(0, 0).unwrap().bg);
+    }
+
+    #[test]
+    fn test_ragged_input() {
+        let frames = Expansion::new("#\n..#\n").frames();
+        assert_eq!(
+            "#...\n...#\ndoubled column 2\nexpanded from 3x2 to 4x2\n",
+            frames.last().unwrap().to_ascii()
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use criterion::{
+    criterion_group, criterion_main, Criterion,
+};
+use day_12::*;
+
+fn criterion_benchmark_part1(c: &mut Criterion) {
+    let input =
+        aoc_common::input::load_day(env!("CARGO_PKG_NAME"))
+            .unwrap();
+
+    let mut group = c.benchmark_group("day_12::part1");
+    for variant in part1::VARIANTS {
+        group.bench_with_input(
+            variant.name,
+            input.as_str(),
+            |b, input| b.iter(|| (variant.process)(input)),
+        );
+    }
+
+    group.finish();
+}
+
+fn criterion_benchmark_part2(c: &mut Criterion) {
+    let input =
+        aoc_common::input::load_day(env!("CARGO_PKG_NAME"))
+            .unwrap();
+
+    let mut group = c.benchmark_group("day_12::part2");
+    group.bench_with_input(
+        "part2",
+        input.as_str(),
+        |b, input| b.iter(|| part2::process(input)),
+    );
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    criterion_benchmark_part1,
+    criterion_benchmark_part2
+);
+criterion_main!(benches);
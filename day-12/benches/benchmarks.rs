@@ -0,0 +1,30 @@
+use aoc_common::variants::Variant;
+use day_12::{custom_error::AocError, *};
+
+fn main() {
+    // Run registered benchmarks.
+    divan::main();
+}
+
+#[divan::bench(args = part1::VARIANTS)]
+fn part1(
+    bencher: divan::Bencher,
+    variant: &Variant<u32, AocError>,
+) {
+    let input =
+        aoc_common::input::load_day(env!("CARGO_PKG_NAME"))
+            .unwrap();
+    bencher.bench(|| {
+        (variant.process)(divan::black_box(&input)).unwrap()
+    });
+}
+
+#[divan::bench]
+fn part2(bencher: divan::Bencher) {
+    let input =
+        aoc_common::input::load_day(env!("CARGO_PKG_NAME"))
+            .unwrap();
+    bencher.bench(|| {
+        part2::process(divan::black_box(&input)).unwrap()
+    });
+}
@@ -1,6 +1,23 @@
 use core::panic;
+use std::io::BufRead;
 
-use crate::custom_error::AocError;
+use aoc_common::{
+    lines::{input_lines, try_for_each_line},
+    variants::Variant,
+};
+
+use crate::{custom_error::AocError, part2};
+
+pub const VARIANTS: &[Variant<u32, AocError>] = &[
+    Variant {
+        name: "naive",
+        process,
+    },
+    Variant {
+        name: "memoised",
+        process: process_memoised,
+    },
+];
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Spring {
@@ -75,7 +92,7 @@ impl<'a> SpringProblem<'a> {
                     springs: &springs[i + rec + 1..],
                     record,
                 }
-                    .arrangements(solution_count),
+                .arrangements(solution_count),
                 _ => solution_count,
             };
 
@@ -92,7 +109,6 @@ impl<'a> SpringProblem<'a> {
 }
 
 fn parse_line(line: &str) -> (Vec<Spring>, Vec<usize>) {
-    dbg!(line);
     let mut pieces = line.split(" ");
     let springs = pieces
         .next()
@@ -118,21 +134,50 @@ fn parse_line(line: &str) -> (Vec<Spring>, Vec<usize>) {
 pub fn process(
     input: &str,
 ) -> miette::Result<u32, AocError> {
-    Ok(input
-        .lines()
-        .map(|line| {
-            let (springs, record) = parse_line(line);
-            SpringProblem {
-                springs: &springs,
-                record: &record,
-            }
-                .arrangements(0)
-        })
-        .sum())
+    Ok(input_lines(input).map(line_arrangements).sum())
+}
+
+/// Part 2's memoised search without unfolding the
+/// records
+pub fn process_memoised(
+    input: &str,
+) -> miette::Result<u32, AocError> {
+    let sum = input_lines(input)
+        .map(|line| part2::unfolded_arrangements(line, 1))
+        .sum::<u64>();
+    Ok(u32::try_from(sum).expect("Too many arrangements"))
+}
+
+/// Same as [`process`], reading one line at a
+/// time
+pub fn process_reader(
+    reader: impl BufRead,
+) -> miette::Result<u32, AocError> {
+    let mut sum = 0;
+    try_for_each_line(reader, |line| {
+        sum += line_arrangements(line);
+        Ok::<_, AocError>(())
+    })?;
+    Ok(sum)
+}
+
+fn line_arrangements(line: &str) -> u32 {
+    let (springs, record) = parse_line(line);
+    SpringProblem {
+        springs: &springs,
+        record: &record,
+    }
+    .arrangements(0)
 }
 
 #[cfg(test)]
 mod tests {
+    use aoc_common::{
+        lines::assert_all_formats,
+        variants::{
+            assert_variants, assert_variants_agree_on_input,
+        },
+    };
     use rstest::*;
 
     use super::*;
@@ -158,6 +203,7 @@ mod tests {
         };
         let solution_count = problem.arrangements(0);
         assert_eq!(solution_count, expected);
+        assert_variants(VARIANTS, line, expected);
     }
 
     const TEST_INPUT: &str = "???.### 1,1,3
@@ -169,7 +215,24 @@ mod tests {
 
     #[test]
     fn test_process() -> miette::Result<()> {
-        assert_eq!(21, process(TEST_INPUT)?);
+        assert_variants(VARIANTS, TEST_INPUT, 21);
+        assert_all_formats(
+            |input| process_reader(input.as_bytes()),
+            TEST_INPUT,
+            21,
+        );
+        assert_eq!(
+            21,
+            process_reader(TEST_INPUT.as_bytes())?
+        );
         Ok(())
     }
+
+    #[test]
+    fn test_variants_agree_on_input() {
+        assert_variants_agree_on_input(
+            VARIANTS,
+            env!("CARGO_PKG_NAME"),
+        );
+    }
 }
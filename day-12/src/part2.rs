@@ -1,15 +1,21 @@
 use core::panic;
+use std::io::BufRead;
+
+use aoc_common::lines::{input_lines, try_for_each_line};
 
 use hashbrown::HashMap;
-use rayon::{
-    *,
-    iter::{IntoParallelIterator, ParallelIterator},
-    str::ParallelString,
-};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use regex::Regex;
 
 use crate::custom_error::AocError;
 
+aoc_common::params! {
+    pub struct Params {
+        /// how many copies of each line to unfold
+        copies: usize = 5,
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Spring {
     Operational,
@@ -37,16 +43,10 @@ impl<'a> SpringProblem<'a> {
                     Spring::Unknown | Spring::Operational
                 )
             }) {
-                memo.insert(
-                    (self.springs, self.record),
-                    1,
-                );
+                memo.insert((self.springs, self.record), 1);
                 return 1;
             }
-            memo.insert(
-                (self.springs, self.record),
-                0,
-            );
+            memo.insert((self.springs, self.record), 0);
             return 0;
         }
 
@@ -95,14 +95,12 @@ impl<'a> SpringProblem<'a> {
                             Spring::Unknown
                             | Spring::Operational,
                         ),
-                    ) => {
-                        SpringProblem {
-                            springs: &springs[i + rec + 1..],
-                            record,
-                        }
-                            .arrangements(memo)
+                    ) => SpringProblem {
+                        springs: &springs[i + rec + 1..],
+                        record,
                     }
-                    _ => 0
+                    .arrangements(memo),
+                    _ => 0,
                 };
 
             // If we see a damaged spring at first position,
@@ -121,12 +119,14 @@ impl<'a> SpringProblem<'a> {
     }
 }
 
-fn parse_line(line: &str) -> (Vec<Spring>, Vec<usize>) {
+fn parse_line(
+    line: &str,
+    copies: usize,
+) -> (Vec<Spring>, Vec<usize>) {
     let mut pieces = line.split(" ");
     let springs = pieces.next().expect("No springs found");
     let springs: String =
-        std::iter::repeat(springs.chars())
-            .take(5)
+        std::iter::repeat_n(springs.chars(), copies)
             .intersperse("?".chars())
             .flatten()
             .collect();
@@ -137,7 +137,7 @@ fn parse_line(line: &str) -> (Vec<Spring>, Vec<usize>) {
     // since we don't need to check if we are
     // out of bounds after finding the last damaged
     // spring
-    match (&springs).chars().last() {
+    match springs.chars().last() {
         Some('.') => (),
         _ => springs += ".",
     };
@@ -157,8 +157,7 @@ fn parse_line(line: &str) -> (Vec<Spring>, Vec<usize>) {
         .split(",")
         .map(|s| s.parse().expect("Invalid number"))
         .collect::<Vec<usize>>();
-    let record = std::iter::repeat(record)
-        .take(5)
+    let record = std::iter::repeat_n(record, copies)
         .flatten()
         .collect::<Vec<usize>>();
     assert!(pieces.next().is_none());
@@ -169,21 +168,62 @@ fn parse_line(line: &str) -> (Vec<Spring>, Vec<usize>) {
 pub fn process(
     input: &str,
 ) -> miette::Result<u64, AocError> {
-    Ok(input
-        .par_lines()
+    process_with(input, &Params::default())
+}
+
+/// Same as [`process`], unfolding as many copies
+/// as `params` says
+pub fn process_with(
+    input: &str,
+    params: &Params,
+) -> miette::Result<u64, AocError> {
+    Ok(input_lines(input)
+        .collect::<Vec<&str>>()
         .into_par_iter()
         .map(|line| {
-            let (springs, record) = parse_line(line);
-            let memo = &mut HashMap::new();
-            SpringProblem {
-                springs: &springs,
-                record: &record,
-            }
-                .arrangements(memo)
+            unfolded_arrangements(line, params.copies)
         })
         .sum())
 }
 
+/// Same as [`process`], reading one line at a
+/// time instead of solving lines in parallel
+pub fn process_reader(
+    reader: impl BufRead,
+) -> miette::Result<u64, AocError> {
+    process_reader_with(reader, &Params::default())
+}
+
+/// Same as [`process_with`], reading one line at
+/// a time
+pub fn process_reader_with(
+    reader: impl BufRead,
+    params: &Params,
+) -> miette::Result<u64, AocError> {
+    let mut sum = 0;
+    try_for_each_line(reader, |line| {
+        sum += unfolded_arrangements(line, params.copies);
+        Ok::<_, AocError>(())
+    })?;
+    Ok(sum)
+}
+
+/// Arrangements of the line's record repeated
+/// `copies` times, so part 1 can use the memoised
+/// search with a single copy
+pub(crate) fn unfolded_arrangements(
+    line: &str,
+    copies: usize,
+) -> u64 {
+    let (springs, record) = parse_line(line, copies);
+    let memo = &mut HashMap::new();
+    SpringProblem {
+        springs: &springs,
+        record: &record,
+    }
+    .arrangements(memo)
+}
+
 fn replace_dot_series_with_one(s: &str) -> String {
     Regex::new(r"\.{2,}")
         .unwrap()
@@ -193,6 +233,9 @@ fn replace_dot_series_with_one(s: &str) -> String {
 
 #[cfg(test)]
 mod tests {
+    use aoc_common::{
+        lines::assert_all_formats, params::Params as _,
+    };
     use hashbrown::HashMap;
 
     use super::*;
@@ -225,7 +268,7 @@ mod tests {
     #[test]
     fn test_parse_line() {
         let line = "?..#? 1,1";
-        let (springs, record) = parse_line(line);
+        let (springs, record) = parse_line(line, 5);
         assert_eq!(
             vec![1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
             record
@@ -266,7 +309,7 @@ mod tests {
     fn test_arrangements() {
         let line = ".??..??...?##. 1,1,3";
         let expected = 16384;
-        let (springs, record) = parse_line(line);
+        let (springs, record) = parse_line(line, 5);
         let memo = &mut HashMap::new();
         let problem = SpringProblem {
             springs: &springs,
@@ -286,6 +329,21 @@ mod tests {
     #[test]
     fn test_process() -> miette::Result<()> {
         assert_eq!(525152, process(TEST_INPUT)?);
+        assert_all_formats(process, TEST_INPUT, 525152);
+        assert_eq!(
+            525152,
+            process_reader(TEST_INPUT.as_bytes())?
+        );
+        // a single copy is part 1
+        let params = Params::with(["copies=1"])?;
+        assert_eq!(21, process_with(TEST_INPUT, &params)?);
+        assert_eq!(
+            21,
+            process_reader_with(
+                TEST_INPUT.as_bytes(),
+                &params
+            )?
+        );
         Ok(())
     }
 }
@@ -67,19 +67,21 @@ fn main() -> Result<(), reqwest::Error> {
         .send()?
         .text()?;
 
-    for filename in ["input1.txt", "input2.txt"] {
-        let file_path = args
-            .current_working_directory
-            .join(&args.day)
-            .join(filename);
-        let mut file = File::create(&file_path)
-            .expect("should be able to create a file");
+    // the default user's input, shared by both
+    // parts
+    let dir = args
+        .current_working_directory
+        .join("inputs")
+        .join("default");
+    std::fs::create_dir_all(&dir)
+        .expect("should be able to create the inputs directory");
+    let file_path = dir.join(format!("{}.txt", args.day));
+    let mut file = File::create(&file_path)
+        .expect("should be able to create a file");
 
-        file.write_all(input_data.as_bytes()).expect(
-            "should be able to write to input file",
-        );
-        println!("wrote {}", file_path.display());
-    }
+    file.write_all(input_data.as_bytes())
+        .expect("should be able to write to input file");
+    println!("wrote {}", file_path.display());
 
     Ok(())
 }